@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Recorded correct answers for one day, stored in `answers/{year}-{day}.toml`:
+///
+/// ```toml
+/// part_one = "66487"
+/// part_two = "197301"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answers {
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+/// How a result compares to the recorded answer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    New,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "✅"),
+            Verdict::Wrong => write!(f, "❌"),
+            Verdict::New => write!(f, "new"),
+        }
+    }
+}
+
+impl Answers {
+    pub fn path(year: u16, day: u8) -> PathBuf {
+        Path::new("answers").join(format!("{}-{:02}.toml", year, day))
+    }
+
+    /// Loads the answers for a day, or no answers if none have been recorded yet.
+    pub fn load(year: u16, day: u8) -> io::Result<Self> {
+        Self::load_from(&Self::path(year, day))
+    }
+
+    /// A file that can't be parsed is an error rather than no answers, so that results
+    /// aren't all reported as new and the file isn't overwritten by `--record-answers`.
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        let s = match fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+        toml::from_str(&s).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+    }
+
+    pub fn save(&self, year: u16, day: u8) -> io::Result<()> {
+        self.save_to(&Self::path(year, day))
+    }
+
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let s = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, s)
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_one.as_deref(),
+            2 => self.part_two.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, part: u8, answer: &str) {
+        match part {
+            1 => self.part_one = Some(answer.to_string()),
+            2 => self.part_two = Some(answer.to_string()),
+            _ => panic!("there is no part {}", part),
+        }
+    }
+
+    /// Checks `answer` against the recorded one. Returns `None` if there is neither an
+    /// answer nor a recorded one, e.g. for a part that hasn't been attempted yet.
+    pub fn check(&self, part: u8, answer: Option<&str>) -> Option<Verdict> {
+        match (self.get(part), answer) {
+            (None, None) => None,
+            (None, Some(_)) => Some(Verdict::New),
+            (Some(expected), Some(answer)) if expected == answer => Some(Verdict::Correct),
+            (Some(_), _) => Some(Verdict::Wrong),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        assert_eq!(
+            Answers::path(2022, 7),
+            PathBuf::from("answers/2022-07.toml")
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers {
+            part_one: Some("42".to_string()),
+            part_two: None,
+        };
+        assert_eq!(answers.check(1, Some("42")), Some(Verdict::Correct));
+        assert_eq!(answers.check(1, Some("41")), Some(Verdict::Wrong));
+        assert_eq!(answers.check(1, None), Some(Verdict::Wrong));
+        assert_eq!(answers.check(2, Some("7")), Some(Verdict::New));
+        assert_eq!(answers.check(2, None), None);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("aoc-2022-answers-test-{}.toml", std::process::id()));
+        let mut answers = Answers::default();
+        answers.set(1, "CMZ");
+        answers.set(2, "#..#\n.##.");
+        answers.save_to(&path).unwrap();
+
+        assert_eq!(Answers::load_from(&path).unwrap(), answers);
+
+        fs::write(&path, "part_one = ").unwrap();
+        let err = Answers::load_from(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(Answers::load_from(&path).unwrap(), Answers::default());
+    }
+
+    #[test]
+    fn test_recorded_answers_parse() {
+        let answers = Answers::load(2022, 10).unwrap();
+        assert_eq!(answers.get(1), Some("13720"));
+        assert_eq!(answers.get(2).map(|a| a.lines().count()), Some(6));
+    }
+}
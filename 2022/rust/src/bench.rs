@@ -0,0 +1,541 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::record::{self, Record, Status};
+use crate::runner::{format_table, Solver};
+use crate::{try_read_file, ANSI_BOLD, ANSI_RESET};
+
+/// Command line flag that switches to benchmarking mode.
+pub const BENCH_FLAG: &str = "--bench";
+
+/// Options for `--bench`, all read from the command line:
+///
+/// * `--bench-time <ms>`: time budget for measuring each part (default 1000)
+/// * `--warmup <ms>`: time spent running each part before measuring (default 100)
+/// * `--baseline <path>`: baseline file (default `target/bench-baseline.json`)
+/// * `--save-baseline`: store this run's medians as the new baseline
+/// * `--threshold <percent>`: slowdown that counts as a regression (default 10)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchConfig {
+    pub budget: Duration,
+    pub warmup: Duration,
+    pub baseline: PathBuf,
+    pub save_baseline: bool,
+    pub threshold: f64,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            budget: Duration::from_millis(1000),
+            warmup: Duration::from_millis(100),
+            baseline: PathBuf::from("target/bench-baseline.json"),
+            save_baseline: false,
+            threshold: 10.0,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Returns the benchmark options if `--bench` was passed. Exits if an option
+    /// can't be parsed, rather than benchmarking with settings nobody asked for.
+    pub fn from_args() -> Option<Self> {
+        let mut args = pico_args::Arguments::from_env();
+        match Self::parse(&mut args) {
+            Ok(Some(config)) => {
+                let unused = args.finish();
+                if !unused.is_empty() {
+                    eprintln!("Ignoring unused arguments: {:?}", unused);
+                }
+                Some(config)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                eprintln!("Bad benchmark option: {}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    fn parse(args: &mut pico_args::Arguments) -> Result<Option<Self>, pico_args::Error> {
+        if !args.contains(BENCH_FLAG) {
+            return Ok(None);
+        }
+        let mut config = Self::default();
+        if let Some(ms) = args.opt_value_from_str("--bench-time")? {
+            config.budget = Duration::from_millis(ms);
+        }
+        if let Some(ms) = args.opt_value_from_str("--warmup")? {
+            config.warmup = Duration::from_millis(ms);
+        }
+        if let Some(path) = args.opt_value_from_str::<_, String>("--baseline")? {
+            config.baseline = path.into();
+        }
+        if let Some(threshold) = args.opt_value_from_str("--threshold")? {
+            config.threshold = threshold;
+        }
+        config.save_baseline = args.contains("--save-baseline");
+        // Read by the output code, not here
+        args.contains(record::JSON_FLAG);
+        Ok(Some(config))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub samples: usize,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub stddev_ns: u64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [u64]) -> Self {
+        assert!(!samples.is_empty(), "need at least one sample");
+        samples.sort_unstable();
+
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+        let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            samples
+                .iter()
+                .map(|&s| (s as f64 - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Stats {
+            samples: n,
+            min_ns: samples[0],
+            median_ns: median,
+            mean_ns: mean.round() as u64,
+            stddev_ns: variance.sqrt().round() as u64,
+        }
+    }
+}
+
+/// Runs `func` for `config.warmup`, then keeps sampling it until `config.budget` is used up.
+/// At least one sample is always taken.
+pub fn measure<T>(func: impl Fn(&str) -> Option<T>, input: &str, config: &BenchConfig) -> Stats {
+    let warmup = Instant::now();
+    while warmup.elapsed() < config.warmup {
+        func(input);
+    }
+
+    let mut samples = vec![];
+    let budget = Instant::now();
+    while samples.is_empty() || budget.elapsed() < config.budget {
+        let timer = Instant::now();
+        func(input);
+        samples.push(timer.elapsed().as_nanos() as u64);
+    }
+    Stats::from_samples(&mut samples)
+}
+
+/// Median timings from an earlier run, keyed by day and part.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub medians_ns: BTreeMap<String, u64>,
+}
+
+fn baseline_key(day: u8, part: u8) -> String {
+    format!("{:02}-{}", day, part)
+}
+
+impl Baseline {
+    /// Loads the baseline at `path`, or an empty one if there is none yet. A file that
+    /// can't be parsed is an error rather than an empty baseline, so that it isn't
+    /// silently overwritten by the next `--save-baseline`.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let s = match fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_str(&s).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<u64> {
+        self.medians_ns.get(&baseline_key(day, part)).copied()
+    }
+
+    pub fn set(&mut self, day: u8, part: u8, median_ns: u64) {
+        self.medians_ns.insert(baseline_key(day, part), median_ns);
+    }
+}
+
+/// How a median compares to the baseline, as a percentage change.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Regressed(f64),
+    Improved(f64),
+    Unchanged(f64),
+}
+
+impl Change {
+    pub fn new(baseline_ns: u64, median_ns: u64, threshold: f64) -> Self {
+        let pct = (median_ns as f64 - baseline_ns as f64) / baseline_ns.max(1) as f64 * 100.0;
+        if pct > threshold {
+            Change::Regressed(pct)
+        } else if pct < -threshold {
+            Change::Improved(pct)
+        } else {
+            Change::Unchanged(pct)
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Regressed(pct) => write!(f, "⚠️ {:.1}% slower", pct),
+            Change::Improved(pct) => write!(f, "{:.1}% faster", -pct),
+            Change::Unchanged(pct) => write!(f, "{:+.1}%", pct),
+        }
+    }
+}
+
+/// The benchmark outcome for one part. `stats` is only set for solved parts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchRecord {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub status: Status,
+    pub stats: Option<Stats>,
+    pub baseline_median_ns: Option<u64>,
+}
+
+impl BenchRecord {
+    pub fn change(&self, threshold: f64) -> Option<Change> {
+        let median = self.stats.as_ref()?.median_ns;
+        Some(Change::new(self.baseline_median_ns?, median, threshold))
+    }
+}
+
+/// Benchmarks one part. The part is run once first so that panicking or unsolved
+/// parts are reported instead of measured.
+pub fn bench_part<T: Display>(
+    day: u8,
+    part: u8,
+    func: impl Fn(&str) -> Option<T>,
+    input: &str,
+    config: &BenchConfig,
+    baseline: &Baseline,
+) -> BenchRecord {
+    let record = Record::capture(day, part, &func, input);
+    let stats = match record.status {
+        Status::Solved => Some(measure(&func, input, config)),
+        _ => None,
+    };
+    BenchRecord {
+        day,
+        part,
+        answer: record.answer,
+        status: record.status,
+        stats,
+        baseline_median_ns: baseline.get(day, part),
+    }
+}
+
+fn ns(ns: u64) -> String {
+    format!("{:.2?}", Duration::from_nanos(ns))
+}
+
+/// Renders benchmark results as a table.
+pub fn render_table(records: &[BenchRecord], threshold: f64) -> String {
+    let header = [
+        "Day", "Part", "Answer", "Samples", "Min", "Median", "Mean", "Stddev", "Baseline",
+    ]
+    .map(String::from)
+    .to_vec();
+    let rows = records
+        .iter()
+        .map(|r| {
+            let answer = match (&r.status, &r.answer) {
+                (Status::Solved, Some(answer)) if answer.contains('\n') => "(multi-line)".into(),
+                (Status::Solved, Some(answer)) => answer.clone(),
+                (Status::Panicked, _) => "panicked".into(),
+                _ => "not solved".into(),
+            };
+            let mut row = vec![format!("{:02}", r.day), r.part.to_string(), answer];
+            if let Some(stats) = &r.stats {
+                row.extend([
+                    stats.samples.to_string(),
+                    ns(stats.min_ns),
+                    ns(stats.median_ns),
+                    ns(stats.mean_ns),
+                    ns(stats.stddev_ns),
+                    r.change(threshold)
+                        .map(|c| c.to_string())
+                        .unwrap_or_else(|| "new".into()),
+                ]);
+            }
+            row
+        })
+        .collect();
+    format_table(&[vec![header], rows], &[3, 4, 5, 6, 7])
+}
+
+fn print_record(record: &BenchRecord, threshold: f64) {
+    if record::json_enabled() {
+        println!("{}", serde_json::to_string(record).unwrap());
+        return;
+    }
+    let note = match &record.stats {
+        Some(stats) => format!(
+            "min: {}, median: {}, mean: {} ± {}, {} samples",
+            ns(stats.min_ns),
+            ns(stats.median_ns),
+            ns(stats.mean_ns),
+            ns(stats.stddev_ns),
+            stats.samples,
+        ),
+        None => String::new(),
+    };
+    record::print_part(record.part, record.status, record.answer.as_deref(), &note);
+    if let Some(change) = record.change(threshold) {
+        println!(
+            "vs. baseline {}: {}",
+            ns(record.baseline_median_ns.unwrap()),
+            change
+        );
+    }
+}
+
+/// The baseline to compare against. If it can't be read the run goes ahead without
+/// one, after saying why.
+fn load_baseline(config: &BenchConfig) -> Baseline {
+    Baseline::load(&config.baseline).unwrap_or_else(|e| {
+        eprintln!(
+            "Couldn't read the baseline, not comparing against it: {}",
+            e
+        );
+        Baseline::default()
+    })
+}
+
+fn save_baseline(records: &[BenchRecord], config: &BenchConfig) {
+    // Merge into the existing file so single-day runs don't drop the other days
+    let mut baseline = match Baseline::load(&config.baseline) {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            eprintln!("Not saving the baseline over an unreadable one: {}", e);
+            return;
+        }
+    };
+    for record in records {
+        if let Some(stats) = &record.stats {
+            baseline.set(record.day, record.part, stats.median_ns);
+        }
+    }
+    match baseline.save(&config.baseline) {
+        Ok(_) => eprintln!("Saved baseline to \"{}\"", config.baseline.display()),
+        Err(e) => eprintln!("Failed to save baseline: {}", e),
+    }
+}
+
+/// Benchmarks a single part from a day's binary; used by `solve!` when `--bench` is passed.
+pub fn bench_and_print<T: Display>(
+    day: u8,
+    part: u8,
+    func: impl Fn(&str) -> Option<T>,
+    input: &str,
+    config: &BenchConfig,
+) -> BenchRecord {
+    let baseline = load_baseline(config);
+    let record = bench_part(day, part, func, input, config, &baseline);
+    print_record(&record, config.threshold);
+    if config.save_baseline {
+        save_baseline(std::slice::from_ref(&record), config);
+    }
+    record
+}
+
+/// Benchmarks every solver that has an input and prints a table of the results.
+pub fn bench_all(solvers: &[Solver], config: &BenchConfig) -> Vec<BenchRecord> {
+    let baseline = load_baseline(config);
+    let json = record::json_enabled();
+
+    let mut records = vec![];
+    for solver in solvers {
+        let input = match try_read_file("inputs", solver.day) {
+            Some(input) => input,
+            None => continue,
+        };
+        for (part, func) in [(1, solver.part_one), (2, solver.part_two)] {
+            let record = bench_part(solver.day, part, func, &input, config, &baseline);
+            if json {
+                print_record(&record, config.threshold);
+            }
+            records.push(record);
+        }
+    }
+
+    if !json {
+        println!("{}", render_table(&records, config.threshold));
+        let regressions = records
+            .iter()
+            .filter(|r| matches!(r.change(config.threshold), Some(Change::Regressed(_))))
+            .count();
+        if regressions > 0 {
+            println!(
+                "{}{} part(s) regressed by more than {}%{}",
+                ANSI_BOLD, regressions, config.threshold, ANSI_RESET
+            );
+        }
+    }
+    if config.save_baseline {
+        save_baseline(&records, config);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&mut [5, 1, 3, 2, 4]);
+        assert_eq!(
+            stats,
+            Stats {
+                samples: 5,
+                min_ns: 1,
+                median_ns: 3,
+                mean_ns: 3,
+                stddev_ns: 2,
+            }
+        );
+
+        let stats = Stats::from_samples(&mut [10, 20]);
+        assert_eq!(stats.median_ns, 15);
+        assert_eq!(stats.stddev_ns, 7);
+
+        let stats = Stats::from_samples(&mut [7]);
+        assert_eq!(stats.median_ns, 7);
+        assert_eq!(stats.stddev_ns, 0);
+    }
+
+    #[test]
+    fn test_measure_takes_a_sample() {
+        let config = BenchConfig {
+            budget: Duration::ZERO,
+            warmup: Duration::ZERO,
+            ..Default::default()
+        };
+        let stats = measure(|input: &str| Some(input.len()), "abc", &config);
+        assert_eq!(stats.samples, 1);
+    }
+
+    #[test]
+    fn test_change() {
+        assert_eq!(Change::new(100, 120, 10.0), Change::Regressed(20.0));
+        assert_eq!(Change::new(100, 80, 10.0), Change::Improved(-20.0));
+        assert_eq!(Change::new(100, 105, 10.0), Change::Unchanged(5.0));
+        assert_eq!(Change::new(100, 105, 1.0), Change::Regressed(5.0));
+    }
+
+    #[test]
+    fn test_baseline_roundtrip() {
+        let path = std::env::temp_dir().join(format!(
+            "aoc-2022-bench-baseline-test-{}.json",
+            std::process::id()
+        ));
+        let mut baseline = Baseline::default();
+        baseline.set(1, 2, 1234);
+        baseline.save(&path).unwrap();
+
+        let loaded = Baseline::load(&path).unwrap();
+        assert_eq!(loaded.get(1, 2), Some(1234));
+        assert_eq!(loaded.get(1, 1), None);
+
+        fs::write(&path, "{ not json").unwrap();
+        let err = Baseline::load(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(Baseline::load(&path).unwrap(), Baseline::default());
+    }
+
+    #[test]
+    fn test_bench_part() {
+        let config = BenchConfig {
+            budget: Duration::from_millis(1),
+            warmup: Duration::ZERO,
+            ..Default::default()
+        };
+        let mut baseline = Baseline::default();
+        baseline.set(3, 1, 1);
+
+        let record = bench_part(3, 1, |_: &str| Some(42), "", &config, &baseline);
+        assert_eq!(record.answer, Some("42".to_string()));
+        assert!(record.stats.unwrap().samples >= 1);
+        assert_eq!(record.baseline_median_ns, Some(1));
+
+        let record = bench_part(3, 2, |_: &str| None::<u32>, "", &config, &baseline);
+        assert_eq!(record.status, Status::Unsolved);
+        assert_eq!(record.stats, None);
+        assert_eq!(record.change(10.0), None);
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let parse = |args: &[&str]| {
+            let mut args =
+                pico_args::Arguments::from_vec(args.iter().map(OsString::from).collect());
+            BenchConfig::parse(&mut args).map(|config| (config, args.finish()))
+        };
+        assert_eq!(parse(&[]).unwrap().0, None);
+        assert_eq!(
+            parse(&["--bench"]).unwrap(),
+            (Some(BenchConfig::default()), vec![])
+        );
+        let (config, unused) = parse(&[
+            "--bench",
+            "--bench-time",
+            "50",
+            "--threshold",
+            "2.5",
+            "--save-baseline",
+            "--json",
+            "--extra",
+        ])
+        .unwrap();
+        let config = config.unwrap();
+        assert_eq!(config.budget, Duration::from_millis(50));
+        assert_eq!(config.threshold, 2.5);
+        assert!(config.save_baseline);
+        assert_eq!(unused, vec![OsString::from("--extra")]);
+
+        assert!(parse(&["--bench", "--threshold", "5%"]).is_err());
+        assert!(parse(&["--bench", "--bench-time", "2s"]).is_err());
+    }
+}
@@ -1,157 +1,78 @@
-use std::fmt;
-use std::str::FromStr;
-
-#[derive(Debug, PartialEq, Eq, Hash)]
-struct Pos(usize, usize);
-
-#[derive(Debug)]
-struct SquareGrid {
-    data: Vec<u32>,
-    size: usize,
-}
-
-impl SquareGrid {
-    fn new(size: usize) -> Self {
-        Self {
-            data: vec![0; size * size],
-            size,
-        }
-    }
-
-    fn idx(&self, pos: &Pos) -> usize {
-        pos.0 + (pos.1 * self.size)
-    }
-
-    fn set(&mut self, pos: &Pos, val: u32) {
-        let i = self.idx(pos);
-        self.data[i] = val;
-    }
-
-    fn get(&self, pos: &Pos) -> u32 {
-        let i = self.idx(&pos);
-        self.data[i]
-    }
-
-    fn is_visible(&self, pos: &Pos) -> bool {
-        // A position is visible if we can get from it to any of the four edges, and not pass
-        // another position of greater or equal value
-        // The edges are always visible
-        if pos.0 == 0 || pos.1 == 0 || pos.0 == self.size - 1 || pos.1 == self.size - 1 {
-            return true;
-        }
-        let v = self.get(pos);
-        if !(pos.0 + 1..self.size).any(|x| self.get(&Pos(x, pos.1)) >= v) {
-            return true;
-        }
-        if !(0..pos.0).any(|x| self.get(&Pos(x, pos.1)) >= v) {
-            return true;
+use advent_of_code::helpers::render::{self, Renderer};
+use advent_of_code::helpers::{Coord, Grid as _, VecGrid};
+
+type Grid = VecGrid<u32>;
+
+/// How many trees a tree of `height` can see along `line`, and whether it can see
+/// all the way to the edge.
+fn view(line: impl Iterator<Item = u32>, height: u32) -> (usize, bool) {
+    let mut seen = 0;
+    for tree in line {
+        seen += 1;
+        if tree >= height {
+            return (seen, false);
         }
-        if !(pos.1 + 1..self.size).any(|y| self.get(&Pos(pos.0, y)) >= v) {
-            return true;
-        }
-        if !(0..pos.1).any(|y| self.get(&Pos(pos.0, y)) >= v) {
-            return true;
-        }
-        false
     }
+    (seen, true)
+}
 
-    fn scenic_score(&self, pos: &Pos) -> usize {
-        if pos.0 == 0 || pos.1 == 0 || pos.0 == self.size - 1 || pos.1 == self.size - 1 {
-            return 0;
-        }
-        let v = self.get(pos);
-
-        let mut score = 1;
-        let mut d = 0;
-        for x in (pos.0 + 1)..self.size {
-            d += 1;
-            if self.get(&Pos(x, pos.1)) >= v {
-                break;
-            }
-        }
-        score *= d;
-
-        d = 0;
-        for x in (0..pos.0).rev() {
-            d += 1;
-            if self.get(&Pos(x, pos.1)) >= v {
-                break;
-            }
-        }
-        score *= d;
-
-        d = 0;
-        for y in (pos.1 + 1)..self.size {
-            d += 1;
-            if self.get(&Pos(pos.0, y)) >= v {
-                break;
-            }
-        }
-        score *= d;
-
-        d = 0;
-        for y in (0..pos.1).rev() {
-            d += 1;
-            if self.get(&Pos(pos.0, y)) >= v {
-                break;
-            }
-        }
-        score *= d;
+/// The view from `pos` towards each edge, looking along its row and column in place.
+fn views(grid: &Grid, pos: Coord, height: u32) -> [(usize, bool); 4] {
+    let row = grid.row_slice(pos.y).expect("pos is in the grid");
+    let column = grid.column_slice(pos.x).expect("pos is in the grid");
+    let x = (pos.x - grid.bottom_left.x) as usize;
+    let y = (pos.y - grid.bottom_left.y) as usize;
+    [
+        view(row[x + 1..].iter().flatten().copied(), height),
+        view(row[..x].iter().rev().flatten().copied(), height),
+        view(column.slice(y + 1..).iter().flatten().copied(), height),
+        view(column.slice(..y).iter().rev().flatten().copied(), height),
+    ]
+}
 
-        score
-    }
+fn is_visible(grid: &Grid, pos: Coord, height: u32) -> bool {
+    // Visible from an edge if nothing in the way is as tall
+    views(grid, pos, height).iter().any(|&(_, edge)| edge)
 }
 
-struct ParseGridError;
-impl FromStr for SquareGrid {
-    type Err = ParseGridError;
-    fn from_str(input: &str) -> Result<SquareGrid, Self::Err> {
-        let size = input.lines().nth(0).ok_or(ParseGridError)?.len();
-        let mut g = SquareGrid::new(size);
-
-        for (y, line) in input.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                g.set(&Pos(x, y), c.to_digit(10).ok_or(ParseGridError)?);
-            }
-        }
-        Ok(g)
-    }
+fn scenic_score(grid: &Grid, pos: Coord, height: u32) -> usize {
+    views(grid, pos, height)
+        .iter()
+        .map(|&(seen, _)| seen)
+        .product()
 }
 
-impl fmt::Display for SquareGrid {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for y in 0..self.size {
-            for x in 0..self.size {
-                if self.is_visible(&Pos(x, y)) {
-                    write!(f, "🎄")?;
-                } else {
-                    write!(f, "⬛️")?;
-                }
-            }
-            write!(f, "\n")?;
-        }
-        Ok(())
-    }
+fn print_visible(grid: &Grid) {
+    Renderer::new()
+        .with_viewport(grid.bounds())
+        .layer(|pos| {
+            let tree = match is_visible(grid, *pos, *grid.get(pos)?) {
+                true => "🎄",
+                false => "⬛️",
+            };
+            Some(tree.to_string())
+        })
+        .print();
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let g = input.parse::<SquareGrid>().ok()?;
+    let grid = Grid::parse(input, |c| c.to_digit(10));
 
-    println!("{}", g);
+    if render::enabled() {
+        print_visible(&grid);
+    }
 
     Some(
-        (0..g.size)
-            .flat_map(move |x| (0..g.size).map(move |y| Pos(x, y)))
-            .filter(|pos| g.is_visible(pos))
+        grid.iter()
+            .filter(|&(pos, &height)| is_visible(&grid, pos, height))
             .count(),
     )
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let g = input.parse::<SquareGrid>().ok()?;
-    (0..g.size)
-        .flat_map(move |x| (0..g.size).map(move |y| Pos(x, y)))
-        .map(|pos| g.scenic_score(&pos))
+    let grid = Grid::parse(input, |c| c.to_digit(10));
+    grid.iter()
+        .map(|(pos, &height)| scenic_score(&grid, pos, height))
         .max()
 }
 
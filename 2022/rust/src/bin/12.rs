@@ -1,51 +1,73 @@
-use pathfinding::prelude::{dijkstra, Matrix};
-
-type Coord = (usize, usize);
-
-fn char_to_level(c: &char) -> u8 {
-    match c {
-        'S' => 'a' as u8,
-        'E' => 'z' as u8,
-        _ => *c as u8,
-    }
+use advent_of_code::helpers::search::{bfs, SearchProblem};
+use advent_of_code::helpers::{Coord, VecGrid};
+
+type Grid = VecGrid<u8>;
+
+/// Parses the heightmap into levels, along with the start and end positions.
+fn parse(input: &str) -> (Grid, Coord, Coord) {
+    let (grid, markers) = Grid::from_str_map(input, "SE", |c| {
+        Some(match c {
+            'S' => b'a',
+            'E' => b'z',
+            _ => c as u8,
+        })
+    });
+    (grid, markers[&'S'], markers[&'E'])
 }
 
-fn successors(matrix: &Matrix<char>, pos: &Coord) -> Vec<(Coord, usize)> {
-    let cur_level = char_to_level(matrix.get(*pos).unwrap());
-    let mut result = vec![];
-    for n_pos in matrix.neighbours(*pos, false) {
-        if char_to_level(matrix.get(n_pos).unwrap()) <= cur_level + 1 {
-            result.push((n_pos, 1));
-        }
-    }
-    result
+/// A climb from `start` to the end, only ever going up one level at a time.
+struct Climb<'a> {
+    grid: &'a Grid,
+    start: Coord,
+    end: Coord,
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let g = Matrix::from_rows(input.lines().map(|line| line.chars())).expect("valid input");
+impl SearchProblem for Climb<'_> {
+    type State = Coord;
 
-    let start = g.items().find(|(_, &c)| c == 'S').expect("starting pos").0;
+    fn start(&self) -> Coord {
+        self.start
+    }
 
-    let end = g.items().find(|(_, &c)| c == 'E').expect("starting pos").0;
+    fn successors(&self, pos: &Coord) -> impl IntoIterator<Item = (Coord, usize)> {
+        let cur_level = *self.grid.get(pos).unwrap();
+        pos.neighbors4()
+            .filter(move |n_pos| {
+                self.grid
+                    .get(n_pos)
+                    .is_some_and(|&level| level <= cur_level + 1)
+            })
+            .map(|n_pos| (n_pos, 1))
+    }
 
-    let path = dijkstra(&start, |p| successors(&g, p), |&p| p == end);
+    fn is_goal(&self, pos: &Coord) -> bool {
+        *pos == self.end
+    }
+}
 
-    Some(path?.1)
+pub fn part_one(input: &str) -> Option<usize> {
+    let (grid, start, end) = parse(input);
+
+    let climb = Climb {
+        grid: &grid,
+        start,
+        end,
+    };
+    Some(bfs(&climb).found?.cost)
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let g = Matrix::from_rows(input.lines().map(|line| line.chars())).expect("valid input");
-
-    let end = g.items().find(|(_, &c)| c == 'E').expect("starting pos").0;
-
-    g.items()
-        .filter_map(|(pos, &c)| {
-            if c != 'a' && c != 'S' {
-                return None;
-            }
-
-            let path = dijkstra(&pos, |p| successors(&g, p), |&p| p == end);
-            Some(path?.1)
+    let (grid, _, end) = parse(input);
+
+    grid.iter()
+        .filter(|&(_, &level)| level == b'a')
+        .filter_map(|(start, _)| {
+            let climb = Climb {
+                grid: &grid,
+                start,
+                end,
+            };
+            Some(bfs(&climb).found?.cost)
         })
         .min()
 }
@@ -1,6 +1,13 @@
-use advent_of_code::helpers::{Coord, HashGrid};
+use std::time::Duration;
 
-type Grid = HashGrid<char>;
+use advent_of_code::helpers::image::{self, Animation, Rgb};
+use advent_of_code::helpers::render::{self, CLEAR};
+use advent_of_code::helpers::{Coord, Grid as _, VecGrid};
+
+type Grid = VecGrid<char>;
+
+/// Part two settles tens of thousands of grains, so `--gif` only keeps every so often.
+const GIF_FRAME_EVERY: u32 = 100;
 
 fn draw_lines(grid: &mut Grid, input: &str) {
     for line in input.lines() {
@@ -16,13 +23,13 @@ fn draw_lines(grid: &mut Grid, input: &str) {
             let dx = (b.x - a.x).signum();
             let dy = (b.y - a.y).signum();
 
-            let mut p = a.clone();
+            let mut p = *a;
             while &p != b {
-                grid.set(p.clone(), '#');
+                grid.set(p, '#');
                 p.x += dx;
                 p.y += dy;
             }
-            grid.set(b.clone(), '#');
+            grid.set(*b, '#');
         }
     }
 }
@@ -35,9 +42,12 @@ fn display_func(v: Option<&char>) -> String {
     }
 }
 
-fn clear() {
-    //print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
-    print!("{esc}[1;1H", esc = 27 as char);
+fn colour(v: &char) -> Option<Rgb> {
+    match v {
+        '#' => Some([128, 128, 128]),
+        'o' => Some([230, 200, 80]),
+        _ => None,
+    }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -51,7 +61,7 @@ pub fn part_one(input: &str) -> Option<u32> {
     let mut grains = 0;
 
     loop {
-        let mut grain = spawn_pos.clone();
+        let mut grain = spawn_pos;
         grains += 1;
 
         while grain.y <= floor {
@@ -94,13 +104,22 @@ pub fn part_two(input: &str) -> Option<u32> {
 
     grid.set(spawn_pos, ' ');
 
+    let render = render::enabled();
+    if render {
+        print!("{CLEAR}");
+    }
+    let gif = image::gif_path();
+    let mut animation = Animation::new()
+        .with_scale(2)
+        .with_delay(Duration::from_millis(40));
+
     // The floor is actually the highest value here
     let floor = grid.top_right.y + 2;
 
     let mut grains = 0;
 
     loop {
-        let mut grain = spawn_pos.clone();
+        let mut grain = spawn_pos;
         grains += 1;
 
         loop {
@@ -129,9 +148,12 @@ pub fn part_two(input: &str) -> Option<u32> {
 
             grid.set(grain, 'o');
 
-            // println!("grain {}", grains);
-            // clear();
-            // grid.print(display_func);
+            if render {
+                render::redraw(&grid.render(display_func));
+            }
+            if gif.is_some() && grains % GIF_FRAME_EVERY == 0 {
+                animation.capture(&grid, colour);
+            }
 
             break;
         }
@@ -142,6 +164,13 @@ pub fn part_two(input: &str) -> Option<u32> {
         }
     }
 
+    if let Some(path) = gif {
+        animation.capture(&grid, colour);
+        if let Err(e) = animation.save_gif(&path) {
+            eprintln!("Couldn't save {}: {}", path.display(), e);
+        }
+    }
+
     Some(grains)
 }
 
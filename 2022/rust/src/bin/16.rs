@@ -1,8 +1,8 @@
-use itertools::Itertools;
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap, HashSet},
-};
+use std::collections::HashMap;
+
+use advent_of_code::helpers::bitset::BitSet;
+use advent_of_code::helpers::graph::{Graph, Interner};
+use advent_of_code::helpers::search::{branch_and_bound, SearchProblem};
 
 use nom::{
     branch::alt,
@@ -40,235 +40,165 @@ fn parse_line(input: &str) -> IResult<&str, Valve> {
     Ok((input, valve))
 }
 
-type Valves = HashMap<String, Valve>;
-
-fn parse_input(input: &str) -> Valves {
-    input
-        .lines()
-        .map(|line| {
-            let valve = parse_line(line).unwrap().1;
-            (valve.label.clone(), valve)
-        })
-        .collect::<Valves>()
+/// The valves worth opening, and how many minutes it takes to walk between them.
+/// Valve 0 is `AA`, where the walk starts.
+struct Cave {
+    flow_rates: Vec<usize>,
+    tunnels: Graph,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-struct State {
-    pos: String,
-    time: u32,
-    pressure_released: u32,
-    open: HashSet<String>,
-}
+fn parse_input(input: &str) -> Cave {
+    let valves = input
+        .lines()
+        .map(|line| parse_line(line).unwrap().1)
+        .collect::<Vec<Valve>>();
+
+    let mut names: Interner<String> = Interner::new();
+    let mut tunnels = Graph::default();
+    for valve in &valves {
+        let from = names.intern(valve.label.as_str());
+        for to in &valve.connections {
+            tunnels.add_edge(from, names.intern(to.as_str()), 1);
+        }
+    }
 
-impl Ord for State {
-    fn cmp(&self, other: &State) -> Ordering {
-        (
-            self.time,
-            self.pressure_released,
-            &self.pos,
-            self.open.iter().sorted().collect::<Vec<&String>>(),
+    // Only the start and the valves with some flow are worth walking to
+    let start_valve = valves
+        .iter()
+        .find(|valve| valve.label == "AA")
+        .expect("a valve AA to start from");
+    let interesting = std::iter::once(start_valve)
+        .chain(
+            valves
+                .iter()
+                .filter(|valve| valve.flow_rate > 0 && valve.label != "AA"),
         )
-            .cmp(&(
-                other.time,
-                other.pressure_released,
-                &other.pos,
-                other.open.iter().sorted().collect::<Vec<&String>>(),
-            ))
+        .collect::<Vec<&Valve>>();
+    let keep = interesting
+        .iter()
+        .map(|valve| names.get(&valve.label).unwrap())
+        .collect::<Vec<usize>>();
+    let flow_rates = interesting
+        .iter()
+        .map(|valve| valve.flow_rate as usize)
+        .collect();
+
+    Cave {
+        flow_rates,
+        tunnels: tunnels.contract(&keep),
     }
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+struct State {
+    pos: usize,
+    time_left: usize,
+    /// What the open valves will have released by the time we run out of time.
+    pressure_released: usize,
+    open: BitSet,
 }
 
-impl PartialOrd for State2 {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+/// Walking from valve to valve for `minutes`, opening each one we stop at.
+struct Walk<'a> {
+    cave: &'a Cave,
+    minutes: usize,
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let valves = parse_input(input);
-
-    let mut queue = BinaryHeap::new();
-
-    let mut seen = HashMap::new();
+impl SearchProblem for Walk<'_> {
+    type State = State;
 
-    let mut best = 0;
-
-    queue.push(State {
-        pos: "AA".to_string(),
-        time: 1,
-        pressure_released: 0,
-        open: HashSet::new(),
-    });
-
-    while queue.len() > 0 {
-        let s = queue.pop().unwrap();
-
-        if let Some(p) = seen.get(&(s.time, s.pos.clone())) {
-            if *p >= s.pressure_released {
-                continue;
-            }
-        }
-        seen.insert((s.time, s.pos.clone()), s.pressure_released);
-
-        if s.time == 30 {
-            best = best.max(s.pressure_released);
-            // eprintln!("best: {}", best);
-            continue;
-        }
-
-        let r: u32 = s.open.iter().map(|v| valves[v].flow_rate).sum();
-
-        if valves[&s.pos].flow_rate > 0 && !s.open.contains(&s.pos) {
-            let mut s1 = s.clone();
-            s1.time += 1;
-            s1.pressure_released += r + valves[&s.pos].flow_rate;
-            s1.open.insert(s.pos.clone());
-            queue.push(s1);
-        }
-
-        for v in &valves[&s.pos].connections {
-            let mut s1 = s.clone();
-            s1.time += 1;
-            s1.pos = v.clone();
-            s1.pressure_released += r;
-            queue.push(s1);
+    fn start(&self) -> State {
+        State {
+            pos: 0,
+            time_left: self.minutes,
+            pressure_released: 0,
+            open: BitSet::new(),
         }
     }
 
-    Some(best)
-}
+    fn successors(&self, s: &State) -> impl IntoIterator<Item = (State, usize)> {
+        self.valves_from(s.pos)
+            .filter(|&(to, dist)| {
+                // Never walk back just to open a valve with no flow, like `AA` usually
+                self.cave.flow_rates[to] > 0 && !s.open.contains(to) && dist < s.time_left
+            })
+            .map(|(to, dist)| {
+                // Walking there and then a minute to open it
+                let time_left = s.time_left - dist - 1;
+                let mut open = s.open.clone();
+                open.insert(to);
+                let next = State {
+                    pos: to,
+                    time_left,
+                    pressure_released: s.pressure_released + self.cave.flow_rates[to] * time_left,
+                    open,
+                };
+                (next, dist + 1)
+            })
+    }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-struct State2 {
-    pos: String,
-    e_pos: String,
-    time: u32,
-    pressure_released: u32,
-    open: HashSet<String>,
-}
+    fn score(&self, s: &State) -> usize {
+        s.pressure_released
+    }
 
-impl Ord for State2 {
-    fn cmp(&self, other: &State2) -> Ordering {
-        (
-            self.time,
-            self.pressure_released,
-            &self.pos,
-            &self.e_pos,
-            self.open.iter().sorted().collect::<Vec<&String>>(),
-        )
-            .cmp(&(
-                other.time,
-                other.pressure_released,
-                &other.pos,
-                &other.e_pos,
-                other.open.iter().sorted().collect::<Vec<&String>>(),
-            ))
+    // As if every closed valve could be walked to straight from here
+    fn upper_bound(&self, s: &State) -> usize {
+        s.pressure_released
+            + self
+                .valves_from(s.pos)
+                .filter(|&(to, _)| !s.open.contains(to))
+                .map(|(to, dist)| self.cave.flow_rates[to] * s.time_left.saturating_sub(dist + 1))
+                .sum::<usize>()
     }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let valves = parse_input(input);
-
-    let mut queue = BinaryHeap::new();
-
-    let mut seen = HashMap::new();
-
-    let mut best = 0;
-
-    queue.push(State2 {
-        pos: "AA".to_string(),
-        e_pos: "AA".to_string(),
-        time: 1,
-        pressure_released: 0,
-        open: HashSet::new(),
-    });
-
-    while queue.len() > 0 {
-        let s = queue.pop().unwrap();
-
-        let state_key = if s.pos <= s.e_pos {
-            (s.time, s.pos.clone(), s.e_pos.clone())
-        } else {
-            (s.time, s.e_pos.clone(), s.pos.clone())
-        };
-
-        if let Some(p) = seen.get(&state_key) {
-            if *p >= s.pressure_released {
-                continue;
-            }
-        }
-        seen.insert(state_key, s.pressure_released);
-
-        if s.time >= 26 {
-            best = best.max(s.pressure_released);
-            // eprintln!("best: {}", best);
-            continue;
-        }
-
-        let r: u32 = s.open.iter().map(|v| valves[v].flow_rate).sum();
-
-        let i_can_open = valves[&s.pos].flow_rate > 0 && !s.open.contains(&s.pos);
-        let e_can_open = valves[&s.e_pos].flow_rate > 0 && !s.open.contains(&s.e_pos);
-
-        if i_can_open {
-            // I open, elephant moves
-            let mut s1 = s.clone();
-            s1.time += 1;
-            s1.pressure_released += r + valves[&s.pos].flow_rate;
-            s1.open.insert(s.pos.clone());
-
-            for v in &valves[&s.e_pos].connections {
-                let mut s2 = s1.clone();
-                s2.e_pos = v.clone();
-                queue.push(s2);
-            }
-        }
-
-        if e_can_open {
-            // elephant opens, I move
-            let mut s1 = s.clone();
-            s1.time += 1;
-            s1.pressure_released += r + valves[&s.e_pos].flow_rate;
-            s1.open.insert(s.e_pos.clone());
+impl Walk<'_> {
+    /// Every valve and how long it takes to walk there from `pos`, including `pos`
+    /// itself, since `AA` could have some flow and be worth opening before leaving.
+    fn valves_from(&self, pos: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        std::iter::once((pos, 0)).chain(self.cave.tunnels.neighbors(pos).iter().copied())
+    }
 
-            for v in &valves[&s.pos].connections {
-                let mut s2 = s1.clone();
-                s2.pos = v.clone();
-                queue.push(s2);
-            }
+    /// The most pressure that can be released by opening exactly each set of valves.
+    fn best_by_opened(&self) -> HashMap<BitSet, usize> {
+        let mut best = HashMap::new();
+        let mut stack = vec![self.start()];
+        while let Some(s) = stack.pop() {
+            let b = best.entry(s.open.clone()).or_default();
+            *b = s.pressure_released.max(*b);
+            stack.extend(self.successors(&s).into_iter().map(|(next, _)| next));
         }
+        best
+    }
+}
 
-        if i_can_open && e_can_open && s.pos != s.e_pos {
-            // We both open
-            let mut s1 = s.clone();
-            s1.time += 1;
-            s1.pressure_released += r + valves[&s.e_pos].flow_rate + valves[&s.pos].flow_rate;
-            s1.open.insert(s.e_pos.clone());
-            s1.open.insert(s.pos.clone());
-            queue.push(s1);
-        }
+pub fn part_one(input: &str) -> Option<usize> {
+    let cave = parse_input(input);
+    let walk = Walk {
+        cave: &cave,
+        minutes: 30,
+    };
+    Some(branch_and_bound(&walk).found?.score)
+}
 
-        // Both move
-        for (v1, v2) in valves[&s.pos]
-            .connections
-            .iter()
-            .cartesian_product(&valves[&s.e_pos].connections)
-        {
-            let mut s1 = s.clone();
-            s1.time += 1;
-            s1.pressure_released += r;
-            s1.pos = v1.clone();
-            s1.e_pos = v2.clone();
-            queue.push(s1);
-        }
-    }
+pub fn part_two(input: &str) -> Option<usize> {
+    let cave = parse_input(input);
+    let walk = Walk {
+        cave: &cave,
+        minutes: 26,
+    };
 
-    Some(best)
+    // The elephant and I open different valves, so pair up every two disjoint sets
+    let best = walk.best_by_opened().into_iter().collect::<Vec<_>>();
+    best.iter()
+        .enumerate()
+        .flat_map(|(i, (mine, me))| {
+            best[i..]
+                .iter()
+                .filter(|(theirs, _)| mine.is_disjoint(theirs))
+                .map(move |(_, elephant)| me + elephant)
+        })
+        .max()
 }
 
 fn main() {
@@ -287,6 +217,14 @@ mod tests {
         assert_eq!(part_one(&input), Some(1651));
     }
 
+    #[test]
+    fn test_start_with_flow() {
+        // Opening AA straight away beats coming back for it
+        let input = "Valve AA has flow rate=10; tunnels lead to valves BB\n\
+                     Valve BB has flow rate=1; tunnels lead to valves AA\n";
+        assert_eq!(part_one(input), Some(10 * 29 + 27));
+    }
+
     #[test]
     fn test_part_one_actual() {
         let input = advent_of_code::read_file("inputs", 16);
@@ -1,8 +1,6 @@
-use std::collections::HashMap;
+use advent_of_code::helpers::{cycle, Coord, VecGrid};
 
-use advent_of_code::helpers::{Coord, HashGrid};
-
-type Grid = HashGrid<char>;
+type Grid = VecGrid<char>;
 
 #[derive(Clone, Debug)]
 struct Shape {
@@ -148,45 +146,7 @@ fn print_grid(grid: &Grid, shape: &Shape, max_height: i32) {
     eprintln!("    +-------+\n");
 }
 
-fn run_grid(input: &str, rounds: usize) -> Grid {
-    let shapes = make_shapes();
-    let jets = input.trim().chars().collect::<Vec<char>>();
-
-    let mut g = Grid::new();
-    for x in -1..=7 {
-        g.set(Coord { x, y: -1 }, '-');
-    }
-
-    let mut max_height = 0i32;
-
-    let mut ji = 0;
-
-    for round in 0..rounds {
-        let mut shape = shapes[round % shapes.len()].spawn(max_height + 3);
-
-        loop {
-            let dp = match jets[ji % jets.len()] {
-                '>' => (1, 0),
-                '<' => (-1, 0),
-                c => panic!("invalid jet {}", c),
-            };
-
-            if !shape.do_move(&g, dp) {}
-
-            ji += 1;
-
-            if !shape.do_move(&g, (0, -1)) {
-                break;
-            }
-        }
-
-        max_height = (shape.pos.y + shape.height).max(max_height);
-        shape.solidify(&mut g);
-    }
-
-    g
-}
-
+/// How far down from the top the highest rock in each column is.
 fn get_profile(grid: &Grid) -> [usize; 7] {
     let mut r = [0; 7];
     for x in 0..7 {
@@ -204,128 +164,89 @@ fn get_profile(grid: &Grid) -> [usize; 7] {
     r
 }
 
-// Returns the grid, first round of the cycle, the cycle length, and the height of the cycle, and
-// the jet index
-fn find_cycle(input: &str) -> (Grid, u64, u64, u64, usize) {
-    let shapes = make_shapes();
-    let jets = input.trim().chars().collect::<Vec<char>>();
-
-    let mut g = Grid::new();
-    for x in -1..=7 {
-        g.set(Coord { x, y: -1 }, '-');
-    }
-
-    let mut max_height = 0i32;
-
-    let mut ji = 0;
-
-    let mut round = 0;
-
-    // Cache of (shape, jet index, profile) to (round, height)
-    let mut cache = HashMap::<(usize, usize, [usize; 7]), (usize, i32)>::new();
-
-    loop {
-        let mut shape = shapes[round % shapes.len()].spawn(max_height + 3);
-
-        // eprintln!("== Round {}; max_height: {} ==", round, max_height);
-
-        let profile = get_profile(&g);
-
-        // print_grid(&g, &shape, max_height);
-        // eprintln!("profile: {:?}", profile);
-
-        let cache_key = (round % shapes.len(), ji % jets.len(), profile);
+/// The chamber after some number of rocks have come to rest.
+struct Tower<'a> {
+    grid: Grid,
+    shapes: &'a [Shape],
+    jets: &'a [char],
+    rocks: usize,
+    jet: usize,
+    max_height: i32,
+}
 
-        if let Some((old_round, old_height)) = cache.get(&cache_key) {
-            eprintln!(
-                "found cycle from {} to {} of len {} with height {}",
-                old_round,
-                round,
-                round - old_round,
-                max_height - old_height
-            );
-            return (
-                g,
-                *old_round as u64,
-                (round - old_round) as u64,
-                (max_height - old_height) as u64,
-                ji,
-            );
+impl<'a> Tower<'a> {
+    fn new(shapes: &'a [Shape], jets: &'a [char]) -> Self {
+        let mut grid = Grid::new();
+        for x in -1..=7 {
+            grid.set(Coord { x, y: -1 }, '-');
         }
+        Self {
+            grid,
+            shapes,
+            jets,
+            rocks: 0,
+            jet: 0,
+            max_height: 0,
+        }
+    }
 
-        cache.insert(cache_key, (round, max_height));
+    fn drop_rock(mut self) -> Self {
+        let mut shape = self.shapes[self.rocks % self.shapes.len()].spawn(self.max_height + 3);
 
         loop {
-            let dp = match jets[ji % jets.len()] {
+            let dp = match self.jets[self.jet % self.jets.len()] {
                 '>' => (1, 0),
                 '<' => (-1, 0),
                 c => panic!("invalid jet {}", c),
             };
 
-            if !shape.do_move(&g, dp) {}
+            if !shape.do_move(&self.grid, dp) {}
 
-            ji += 1;
+            self.jet += 1;
 
-            if !shape.do_move(&g, (0, -1)) {
+            if !shape.do_move(&self.grid, (0, -1)) {
                 break;
             }
         }
 
-        max_height = (shape.pos.y + shape.height).max(max_height);
-        // Solidify the shape
-        shape.solidify(&mut g);
+        self.max_height = (shape.pos.y + shape.height).max(self.max_height);
+        shape.solidify(&mut self.grid);
+        self.rocks += 1;
+        self
+    }
 
-        round += 1;
-        // eprintln!();
+    /// What decides how the next rocks will fall: which rock and jet come next, and
+    /// the shape of the top of the tower.
+    fn state(&self) -> (usize, usize, [usize; 7]) {
+        (
+            self.rocks % self.shapes.len(),
+            self.jet % self.jets.len(),
+            get_profile(&self.grid),
+        )
     }
 }
 
-fn fast_run_grid(input: &str, rounds: u64) -> u64 {
-    // Find cycles
-    let (mut g, cycle_start, cycle_len, cycle_height, mut ji) = find_cycle(input);
-
-    // Skip ahead cycles
-    let cycles = (rounds - cycle_start as u64) / (cycle_len as u64);
+fn parse_jets(input: &str) -> Vec<char> {
+    input.trim().chars().collect()
+}
 
-    // Run the remainder
-    let shapes = make_shapes();
-    let jets = input.trim().chars().collect::<Vec<char>>();
-    let round_start = cycle_start + cycles * cycle_len as u64;
+fn run_grid(input: &str, rounds: usize) -> Grid {
+    let (shapes, jets) = (make_shapes(), parse_jets(input));
+    (0..rounds)
+        .fold(Tower::new(&shapes, &jets), |tower, _| tower.drop_rock())
+        .grid
+}
 
-    eprintln!(
-        "skipping ahead {} cycles to round {}",
-        cycles - 1,
-        round_start
+fn fast_run_grid(input: &str, rounds: u64) -> u64 {
+    let (shapes, jets) = (make_shapes(), parse_jets(input));
+    let height = cycle::extrapolate(
+        Tower::new(&shapes, &jets),
+        Tower::drop_rock,
+        Tower::state,
+        |tower| tower.max_height as i64,
+        rounds as usize,
     );
-
-    let mut max_height = g.height as i32 - 1;
-
-    eprintln!("max height: {}", max_height);
-
-    for round in round_start..rounds {
-        let mut shape = shapes[(round % shapes.len() as u64) as usize].spawn(max_height + 3);
-
-        loop {
-            let dp = match jets[ji % jets.len()] {
-                '>' => (1, 0),
-                '<' => (-1, 0),
-                c => panic!("invalid jet {}", c),
-            };
-
-            if !shape.do_move(&g, dp) {}
-
-            ji += 1;
-
-            if !shape.do_move(&g, (0, -1)) {
-                break;
-            }
-        }
-
-        max_height = (shape.pos.y + shape.height).max(max_height);
-        shape.solidify(&mut g);
-    }
-
-    max_height as u64 + (cycles - 1) * cycle_height as u64
+    height as u64
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
@@ -1,6 +1,7 @@
 use rayon::prelude::*;
 use std::collections::BTreeMap;
-use std::collections::BinaryHeap;
+
+use advent_of_code::helpers::search::{branch_and_bound, SearchProblem};
 
 use nom::{
     branch::alt,
@@ -48,10 +49,6 @@ impl State {
         *self.resources.get(&Resource::Geode).unwrap_or(&0usize)
     }
 
-    fn sort_score(&self) -> (usize, usize) {
-        (self.round, self.geodes())
-    }
-
     // Tries to buy a robot of type robot_type
     // Returns a Some(State) if successful
     // None otherwise
@@ -82,15 +79,17 @@ impl State {
     }
 }
 
-impl Blueprint {
-    fn most_geodes(&self, rounds: usize) -> usize {
-        eprintln!("most_geodes for {:?}", self);
-        let mut best = 0;
-
-        let mut best_by_round = BTreeMap::<usize, usize>::new();
-
-        let mut queue = BinaryHeap::new();
+/// Spending `rounds` minutes building robots from one blueprint. Each move saves up
+/// for a robot and then buys it, so a geode robot's whole output is counted as soon
+/// as it's bought.
+struct Mining<'a> {
+    blueprint: &'a Blueprint,
+    rounds: usize,
+    most_expensive_by_type: BTreeMap<Resource, usize>,
+}
 
+impl<'a> Mining<'a> {
+    fn new(blueprint: &'a Blueprint, rounds: usize) -> Self {
         let most_expensive_by_type = [
             Resource::Geode,
             Resource::Ore,
@@ -100,67 +99,77 @@ impl Blueprint {
         .iter()
         .map(|r| {
             (
-                r,
-                self.robot_costs
+                *r,
+                *blueprint
+                    .robot_costs
                     .values()
                     .map(|v| v.get(r).unwrap_or(&0))
                     .max()
                     .unwrap(),
             )
         })
-        .collect::<BTreeMap<&Resource, &usize>>();
+        .collect();
+        Mining {
+            blueprint,
+            rounds,
+            most_expensive_by_type,
+        }
+    }
+}
+
+impl SearchProblem for Mining<'_> {
+    type State = State;
 
-        let s = State {
+    fn start(&self) -> State {
+        State {
             round: 1,
             resources: BTreeMap::new(),
             robots: [(Resource::Ore, 1)].iter().cloned().collect(),
-        };
-
-        queue.push((s.sort_score(), s));
+        }
+    }
 
-        while let Some((_, s)) = queue.pop() {
-            if s.round >= rounds {
-                if s.geodes() > best {
-                    eprintln!("best is: {}, {:?}", s.geodes(), s);
-                    eprintln!("best_per_round: {:?}", best_by_round);
-                    best = s.geodes();
-                }
+    fn successors(&self, s: &State) -> impl IntoIterator<Item = (State, usize)> {
+        let mut next = vec![];
+        for (robot_type, costs) in &self.blueprint.robot_costs {
+            // No need to buy more of this type of robot if we have enough to mine enough
+            // in one minute to build the most expensive type of this robot
+            if robot_type != &Resource::Geode
+                && s.robots.get(robot_type).unwrap_or(&0)
+                    >= &self.most_expensive_by_type[robot_type]
+            {
                 continue;
             }
 
-            if let Some(g) = best_by_round.get(&s.round) {
-                if s.geodes() < *g {
-                    continue;
+            // Save up to buy this robot
+            let mut s1 = s.clone();
+            while s1.round < self.rounds {
+                if let Some(s2) = s1.buy(robot_type, costs, self.rounds - s1.round) {
+                    next.push((s2, 1));
+                    break;
+                } else {
+                    s1.harvest();
                 }
             }
-            if s.geodes() > 0 {
-                best_by_round.insert(s.round, s.geodes());
-            }
+        }
+        next
+    }
 
-            // We can try building a robot
-            for (robot_type, costs) in &self.robot_costs {
-                // No need to buy more of this type of robot if we have enough to mine enough
-                // in one minute to build the most expensive type of this robot
-                if robot_type != &Resource::Geode {
-                    if s.robots.get(robot_type).unwrap_or(&0) >= most_expensive_by_type[robot_type]
-                    {
-                        continue;
-                    }
-                }
+    fn score(&self, s: &State) -> usize {
+        s.geodes()
+    }
 
-                // Save up to buy this robot
-                let mut s1 = s.clone();
-                while s1.round < rounds {
-                    if let Some(s2) = s1.buy(robot_type, costs, rounds - s1.round) {
-                        queue.push((s2.sort_score(), s2));
-                        break;
-                    } else {
-                        s1.harvest();
-                    }
-                }
-            }
-        }
-        best
+    // Even buying a geode robot every remaining round can't do better than this
+    fn upper_bound(&self, s: &State) -> usize {
+        let n = self.rounds.saturating_sub(s.round);
+        s.geodes() + n * (n + 1) / 2
+    }
+}
+
+impl Blueprint {
+    fn most_geodes(&self, rounds: usize) -> usize {
+        branch_and_bound(&Mining::new(self, rounds))
+            .found
+            .map_or(0, |best| best.score)
     }
 
     fn score(&self, rounds: usize) -> usize {
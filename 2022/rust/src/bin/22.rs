@@ -1,4 +1,4 @@
-use advent_of_code::helpers::{Coord, Direction, HashGrid};
+use advent_of_code::helpers::{Coord, Direction, Grid as _, HashGrid, Point3};
 use int_enum::IntEnum;
 use nom::{
     branch::alt, bytes::complete::tag, character::complete::digit1, combinator::map_res,
@@ -30,8 +30,8 @@ impl Person {
     ) {
         // eprintln!("doing {:?}", instr);
         grid.set(self.pos, self.dir.as_char());
-        let mut p = self.pos.clone();
-        let mut d = self.dir.clone();
+        let mut p = self.pos;
+        let mut d = self.dir;
 
         for _ in 0..instr.steps {
             let (next_pos, next_dir) = wrap_func(&p, &d, grid);
@@ -97,21 +97,7 @@ fn parse_instructions(input: &str) -> IResult<&str, Vec<Instruction>> {
 fn parse_input(input: &str) -> (Grid, Vec<Instruction>) {
     let (grid_input, instr_str) = input.split_once("\n\n").unwrap();
 
-    let mut grid = Grid::new();
-    for (y, line) in grid_input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                '.' | '#' => grid.set(
-                    Coord {
-                        x: x as i32,
-                        y: y as i32,
-                    },
-                    c,
-                ),
-                _ => {}
-            }
-        }
-    }
+    let grid = Grid::parse(grid_input, |c| matches!(c, '.' | '#').then_some(c));
 
     let instructions = parse_instructions(instr_str).expect("valid instructions").1;
 
@@ -126,7 +112,7 @@ fn _print_func(c: Option<&char>) -> String {
 }
 
 fn wrap_basic(pos: &Coord, dir: &Direction, grid: &Grid) -> (Coord, Direction) {
-    let mut next_pos = pos.clone();
+    let mut next_pos = *pos;
     loop {
         next_pos = next_pos + dir.offsets();
         next_pos.x = next_pos.x.rem_euclid(grid.width as i32);
@@ -135,7 +121,15 @@ fn wrap_basic(pos: &Coord, dir: &Direction, grid: &Grid) -> (Coord, Direction) {
             break;
         }
     }
-    (next_pos, dir.clone())
+    (next_pos, *dir)
+}
+
+/// The leftmost open tile of the top row.
+fn start_pos(grid: &Grid) -> Coord {
+    let start_x = (0..grid.width)
+        .find(|x| grid.get(&Coord { x: *x as i32, y: 0 }) == Some(&'.'))
+        .expect("starting position") as i32;
+    Coord { x: start_x, y: 0 }
 }
 
 fn walk_path(
@@ -144,13 +138,8 @@ fn walk_path(
 ) -> i32 {
     let (mut grid, instructions) = parse_input(input);
 
-    // Find the starting position
-    let start_x = (0..grid.width)
-        .find(|x| grid.get(&Coord { x: *x as i32, y: 0 }) == Some(&'.'))
-        .expect("starting position") as i32;
-
     let mut p = Person {
-        pos: Coord { x: start_x, y: 0 },
+        pos: start_pos(&grid),
         dir: Right,
     };
 
@@ -171,114 +160,115 @@ type Portals = HashMap<(Coord, Direction), (Coord, Direction)>;
 fn wrap_3d_with_portals(pos: &Coord, dir: &Direction, portals: &Portals) -> (Coord, Direction) {
     let next_pos = *pos + dir.offsets();
     if let Some(n) = portals.get(&(next_pos, *dir)) {
-        n.clone()
+        *n
     } else {
-        (next_pos, dir.clone())
+        (next_pos, *dir)
     }
 }
 
-#[derive(Debug)]
-enum Edge {
-    Corner(Coord, bool), // true = Concave (inside corner)
-    Point(Coord, Direction),
+/// A face of the cube, cut out of the net. `right` and `down` are where the face's `x`
+/// and `y` point once the net is folded up, and `normal` points out of the cube.
+struct Face {
+    corner: Coord,
+    /// The position on the net of each of the face's cells, top left at `0,0`
+    cells: HashGrid<Coord>,
+    right: Point3,
+    down: Point3,
+    normal: Point3,
 }
 
-fn walk_edge(grid: &Grid, start: &Coord, dir: &Direction) -> Vec<Edge> {
-    let mut p = start.clone();
-    let mut d = dir.clone();
-    let mut edge = vec![];
-
-    edge.push(Edge::Corner(p, false));
-    edge.push(Edge::Point(p, d.left()));
-
-    loop {
-        // eprintln!("at {p:?} facing {d:?}");
-        let next_p = p + d.offsets();
-
-        if grid.get(&next_p).is_none() {
-            // Turn right if there's nothing in front of us
-            d.turn_right();
-            // eprintln!("turning right");
-            edge.push(Edge::Corner(p, false));
-            edge.push(Edge::Point(p, d.left()));
-        } else {
-            // Turn left if there's something to our left
-            let l = d.left();
-            if grid.get(&(p + l.offsets())).is_some() {
-                d.turn_left();
-                edge.push(Edge::Corner(p, true));
-                // eprintln!("turning left");
-                edge.push(Edge::Point(p, d.left()));
-                p = p + d.offsets();
-                edge.push(Edge::Point(p, d.left()));
-            } else {
-                p = next_p;
-                edge.push(Edge::Point(p, d.left()));
-            }
+impl Face {
+    /// Which way the edge on the `dir` side of the face points once folded.
+    fn towards(&self, dir: Direction) -> Point3 {
+        match dir {
+            Right => self.right,
+            Down => self.down,
+            Left => -self.right,
+            Up => -self.down,
         }
+    }
 
-        if &p == start {
-            break;
+    /// The face on the `dir` side of this one on the net, folded over that edge.
+    fn fold(&self, dir: Direction, corner: Coord, cells: HashGrid<Coord>) -> Face {
+        // Every edge bends away from the outside of the cube
+        let (right, down, normal) = match dir {
+            Right => (-self.normal, self.down, self.right),
+            Down => (self.right, -self.normal, self.down),
+            Left => (self.normal, self.down, -self.right),
+            Up => (self.right, self.normal, -self.down),
+        };
+        Face {
+            corner,
+            cells,
+            right,
+            down,
+            normal,
         }
     }
+}
 
-    edge
+/// Folds the net up into a cube, starting from the face that `start` is on.
+fn fold_cube(grid: &Grid, start: Coord) -> Vec<Face> {
+    let size = num::integer::gcd(grid.width, grid.height) as i32;
+    let mut net = HashGrid::new();
+    for (pos, _) in grid.iter() {
+        net.set(pos, pos);
+    }
+    let cut = |corner: Coord| net.sub_grid(&(corner, corner + (size - 1, size - 1)));
+
+    let corner = Coord::new((start.x - start.x % size, start.y - start.y % size));
+    let mut faces = vec![Face {
+        corner,
+        cells: cut(corner),
+        right: Point3::from((1, 0, 0)),
+        down: Point3::from((0, 1, 0)),
+        normal: Point3::from((0, 0, -1)),
+    }];
+    let mut i = 0;
+    while i < faces.len() {
+        for dir in Direction::ALL {
+            let corner = faces[i].corner + Coord::new(dir.offsets()) * size;
+            if net.get(&corner).is_some() && faces.iter().all(|face| face.corner != corner) {
+                let face = faces[i].fold(dir, corner, cut(corner));
+                faces.push(face);
+            }
+        }
+        i += 1;
+    }
+    faces
 }
 
 fn generate_portals(input: &str) -> Portals {
-    let mut portals = Portals::new();
     let (grid, _) = parse_input(input);
+    let faces = fold_cube(&grid, start_pos(&grid));
+    let size = faces[0].cells.width as i32;
 
-    // Find the starting position
-    let start_x = (0..grid.width)
-        .find(|x| grid.get(&Coord { x: *x as i32, y: 0 }) == Some(&'.'))
-        .expect("starting position") as i32;
-
-    let start = Coord { x: start_x, y: 0 };
-
-    let edge = walk_edge(&grid, &start, &Right);
-
-    // Walk outwards from corners, creating portals as we go
-    // Stop when both paths turn a corner
-    for i in 0..edge.len() {
-        if let Edge::Corner(_, true) = edge[i] {
-            // eprintln!("corner at {i:}: {ic:?}");
-            let mut j = (i - 1).rem_euclid(edge.len());
-            let mut k = (i + 1).rem_euclid(edge.len());
-            loop {
-                match (&edge[j], &edge[k]) {
-                    (Edge::Point(p1, d1), Edge::Point(p2, d2)) => {
-                        if p1 != p2 {
-                            let src_pos = p1 + d1;
-                            let dest_dir = d2.reverse();
-                            // eprintln!("portal from {src_pos:?} {d1:?} to {p2:?} {dest_dir:?}");
-                            portals.insert((src_pos, *d1), (*p2, dest_dir));
-
-                            let src_pos = p2 + d2;
-                            let dest_dir = d1.reverse();
-                            // eprintln!("portal from {src_pos:?} {d2:?} to {p1:?} {dest_dir:?}");
-                            portals.insert((src_pos, *d2), (*p1, dest_dir));
-                        }
-
-                        j = (j - 1).rem_euclid(edge.len());
-                        k = (k + 1).rem_euclid(edge.len());
-                    }
-                    (Edge::Point(_, _), Edge::Corner(_, _)) => {
-                        // Keep advancing until it's around the corner
-                        k = (k + 1).rem_euclid(edge.len());
-                    }
-                    (Edge::Corner(_, _), Edge::Point(_, _)) => {
-                        j = (j - 1).rem_euclid(edge.len());
-                    }
-                    (Edge::Corner(_, _), Edge::Corner(_, _)) => {
-                        eprintln!("both hit corners\n");
-                        break;
-                    }
+    let mut portals = Portals::new();
+    for face in &faces {
+        for dir in Direction::ALL {
+            let other = faces
+                .iter()
+                .find(|other| other.normal == face.towards(dir))
+                .expect("a face on every side");
+            // Stepping over the edge heads into the cube, away from this face's outside
+            let entry = Direction::ALL
+                .into_iter()
+                .find(|&d| other.towards(d) == -face.normal)
+                .expect("a shared edge");
+
+            // Turn the other face so it's entered heading `dir`, and lay it alongside
+            let turns = (0..4).find(|&n| entry.rotate_cw(n) == dir).unwrap();
+            let turned = (0..turns).fold(other.cells.clone(), |cells, _| cells.rotate_cw());
+            let mut beyond = HashGrid::new();
+            beyond.paste(&turned, face.corner + Coord::new(dir.offsets()) * size);
+
+            for (_, &pos) in face.cells.iter() {
+                if let Some(&to) = beyond.get(&(pos + dir)) {
+                    portals.insert((pos + dir, dir), (to, entry));
                 }
             }
         }
     }
-
     portals
 }
 
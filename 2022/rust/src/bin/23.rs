@@ -1,39 +1,9 @@
 use std::collections::{HashMap, HashSet};
 
-use advent_of_code::helpers::{Coord, HashGrid};
+use advent_of_code::helpers::{Coord, Dir8, HashGrid};
 
 type Grid = HashGrid<char>;
 
-enum OrthDir {
-    North,
-    East,
-    South,
-    West,
-}
-
-use OrthDir::*;
-
-impl OrthDir {
-    fn facing_offsets(&self) -> Vec<(i32, i32)> {
-        match self {
-            North => [(-1, -1), (0, -1), (1, -1)],
-            East => [(1, -1), (1, 0), (1, 1)],
-            South => [(-1, 1), (0, 1), (1, 1)],
-            West => [(-1, -1), (-1, 0), (-1, 1)],
-        }
-        .to_vec()
-    }
-
-    fn offset(&self) -> (i32, i32) {
-        match self {
-            North => (0, -1),
-            East => (1, 0),
-            South => (0, 1),
-            West => (-1, 0),
-        }
-    }
-}
-
 fn print_func(c: Option<&char>) -> String {
     match c {
         Some(c) => c.to_string(),
@@ -41,29 +11,18 @@ fn print_func(c: Option<&char>) -> String {
     }
 }
 
-fn can_move(grid: &Grid, pos: &Coord, dir: &OrthDir) -> bool {
+fn can_move(grid: &Grid, pos: &Coord, dir: Dir8) -> bool {
     // We can move if all the positions we're facing are empty
-    dir.facing_offsets()
-        .iter()
-        .all(|&offset| grid.get(&(*pos + offset)).is_none())
+    [dir.rotate_ccw(1), dir, dir.rotate_cw(1)]
+        .into_iter()
+        .all(|facing| grid.get(&(pos + facing)).is_none())
 }
 
 fn is_alone(grid: &Grid, pos: &Coord) -> bool {
-    for dx in -1..=1 {
-        for dy in -1..=1 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let n = *pos + (dx, dy);
-            if grid.get(&n).is_some() {
-                return false;
-            }
-        }
-    }
-    true
+    !pos.neighbors8().any(|n| grid.get(&n).is_some())
 }
 
-const MOVE_ORDER: [OrthDir; 4] = [North, South, West, East];
+const MOVE_ORDER: [Dir8; 4] = [Dir8::N, Dir8::S, Dir8::W, Dir8::E];
 
 fn next_move(grid: &Grid, pos: &Coord, round: usize) -> Option<(Coord, Coord)> {
     if is_alone(grid, pos) {
@@ -71,9 +30,9 @@ fn next_move(grid: &Grid, pos: &Coord, round: usize) -> Option<(Coord, Coord)> {
     }
 
     for i in 0..4 {
-        let proposed_dir = &MOVE_ORDER[(round + i) % 4];
-        if can_move(grid, pos, &proposed_dir) {
-            return Some((*pos, *pos + proposed_dir.offset()));
+        let proposed_dir = MOVE_ORDER[(round + i) % 4];
+        if can_move(grid, pos, proposed_dir) {
+            return Some((*pos, pos + proposed_dir));
         }
     }
     None
@@ -104,20 +63,7 @@ fn move_elves(grid: &mut Grid, round: usize) {
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut grid = Grid::new();
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, c)| {
-            if c == '#' {
-                grid.set(
-                    Coord {
-                        x: x as i32,
-                        y: y as i32,
-                    },
-                    c,
-                )
-            }
-        })
-    });
+    let mut grid = Grid::parse(input, |c| (c == '#').then_some(c));
 
     for round in 0..10 {
         move_elves(&mut grid, round);
@@ -135,20 +81,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let mut grid = Grid::new();
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, c)| {
-            if c == '#' {
-                grid.set(
-                    Coord {
-                        x: x as i32,
-                        y: y as i32,
-                    },
-                    c,
-                )
-            }
-        })
-    });
+    let mut grid = Grid::parse(input, |c| (c == '#').then_some(c));
 
     let mut round = 0;
     let mut old_positions: HashSet<Coord> = grid.data.keys().cloned().collect();
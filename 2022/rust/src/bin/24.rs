@@ -1,6 +1,6 @@
-use pathfinding::directed::dijkstra::dijkstra;
 use std::collections::HashMap;
 
+use advent_of_code::helpers::timed::TimedGrid;
 use advent_of_code::helpers::{Coord, Direction};
 
 use Direction::*;
@@ -17,144 +17,41 @@ struct Valley {
 }
 
 impl Valley {
-    fn print(&self, round: usize, pos: &Coord) {
-        print!("#");
-        for x in 0..self.width {
-            if pos.y == -1 && pos.x == x as i32 {
-                print!("E");
-            } else if self.start.x == x as i32 {
-                print!(".");
-            } else {
-                print!("#");
-            }
-        }
-        println!("#");
-
-        for y in 0..self.height {
-            print!("#");
-            for x in 0..self.width {
-                let p = Coord::new((x, y));
-                if &p == pos {
-                    print!("E");
-                    if self.blizzards_at(round, &p).len() > 0 {
-                        println!();
-                        panic!("AAH");
-                    }
-                } else {
-                    let b_at_pos = self.blizzards_at(round, &p);
-                    match b_at_pos.len() {
-                        0 => print!("."),
-                        1 => print!("{}", b_at_pos[0]),
-                        n => print!("{}", n),
-                    }
-                }
-            }
-            print!("#\n");
-        }
-
-        print!("#");
-        for x in 0..self.width {
-            if pos.y == self.height as i32 && pos.x == x as i32 {
-                print!("E");
-            } else if self.end.x == x as i32 {
-                print!(".");
-            } else {
-                print!("#");
-            }
-        }
-        println!("#");
+    /// Where every blizzard is after `round` rounds.
+    fn blizzard_positions(&self, round: usize) -> impl Iterator<Item = (Coord, Blizzard)> + '_ {
+        let round = round as i32;
+        self.blizzards.iter().map(move |(pos, &b)| {
+            let pos = match b {
+                Right => Coord::new(((pos.x + round).rem_euclid(self.width), pos.y)),
+                Left => Coord::new(((pos.x - round).rem_euclid(self.width), pos.y)),
+                Down => Coord::new((pos.x, (pos.y + round).rem_euclid(self.height))),
+                Up => Coord::new((pos.x, (pos.y - round).rem_euclid(self.height))),
+            };
+            (pos, b)
+        })
     }
 
     fn repeat_len(&self) -> usize {
         num::integer::lcm(self.width as usize, self.height as usize)
     }
 
-    fn blizzards_at(&self, round: usize, pos: &Coord) -> Vec<Blizzard> {
-        let mut blizzards = vec![];
-        // For each of the 4 directions, figure out if there would be a blizzard moving that
-        // direction at this position
-        if let Some(b) = self.blizzards.get(&Coord {
-            x: (pos.x + round as i32) % self.width,
-            y: pos.y,
-        }) {
-            if b == &Left {
-                blizzards.push(Left)
-            }
-        }
-
-        if let Some(b) = self.blizzards.get(&Coord {
-            x: (pos.x - round as i32).rem_euclid(self.width),
-            y: pos.y,
-        }) {
-            if b == &Right {
-                blizzards.push(Right)
-            }
-        }
-
-        if let Some(b) = self.blizzards.get(&Coord {
-            x: pos.x,
-            y: (pos.y + round as i32) % self.height,
-        }) {
-            if b == &Up {
-                blizzards.push(Up)
-            }
-        }
-
-        if let Some(b) = self.blizzards.get(&Coord {
-            x: pos.x,
-            y: (pos.y - round as i32).rem_euclid(self.height),
-        }) {
-            if b == &Down {
-                blizzards.push(Down)
-            }
-        }
-        blizzards
-    }
-
-    fn is_safe(&self, round: usize, pos: &Coord) -> bool {
-        self.blizzards_at(round, pos).len() == 0
+    fn is_wall(&self, pos: &Coord) -> bool {
+        let inside = (0..self.width).contains(&pos.x) && (0..self.height).contains(&pos.y);
+        !inside && *pos != self.start && *pos != self.end
     }
 
-    // Vec<((round, pos), cost)>
-    fn moves(&self, round: usize, pos: &Coord) -> Vec<((usize, Coord), usize)> {
-        // There are 5 possible positions: left, right, up, down, and staying
-        // For each of them, figure out if any blizzard would be there
-        let mut next_positions = vec![pos.clone()];
-
-        if pos.y == -1 {
-            next_positions.push(pos + Down);
-        } else {
-            if pos.y == self.height - 1 && pos.x == self.end.x {
-                next_positions.push(pos + Down)
-            }
-            if pos.y < self.height - 1 {
-                next_positions.push(pos + Down);
-            }
-
-            if pos.y == 0 && pos.x == self.start.x {
-                next_positions.push(pos + Up);
-            }
-
-            if pos.y > 0 {
-                next_positions.push(pos + Up);
-            }
-
-            if pos.x > 0 {
-                next_positions.push(pos + Left);
-            }
-
-            if pos.x < self.width - 1 {
-                next_positions.push(pos + Right);
-            }
-        }
-
-        let r = next_positions
-            .iter()
-            .filter(|p| self.is_safe((round + 1) % self.repeat_len(), p))
-            .map(|p| (((round + 1) % self.repeat_len(), *p), 1))
-            .collect::<Vec<((usize, Coord), usize)>>();
-
-        r
+    /// Where we can stand in each round, worked out once for a whole cycle of the
+    /// blizzards.
+    fn timed_grid(&self) -> TimedGrid {
+        let bounds = (Coord::new((-1, -1)), Coord::new((self.width, self.height)));
+        let walls = (-1..=self.height)
+            .flat_map(|y| (-1..=self.width).map(move |x| Coord { x, y }))
+            .filter(|pos| self.is_wall(pos))
+            .collect::<Vec<Coord>>();
+        TimedGrid::from_obstacles(bounds, self.repeat_len(), |round| {
+            let blizzards = self.blizzard_positions(round).map(|(pos, _)| pos);
+            walls.iter().copied().chain(blizzards)
+        })
     }
 }
 
@@ -169,7 +66,7 @@ fn parse_input(input: &str) -> Valley {
         width = line.len() as i32;
         for (x, c) in line.chars().enumerate() {
             let pos = Coord::new((x as i32 - 1, y as i32 - 1));
-            if let Some(d) = c.to_string().parse::<Direction>().ok() {
+            if let Ok(d) = c.to_string().parse::<Direction>() {
                 blizzards.insert(pos, d);
             } else {
                 if c == '.' {
@@ -195,46 +92,15 @@ fn parse_input(input: &str) -> Valley {
 
 pub fn part_one(input: &str) -> Option<usize> {
     let valley = parse_input(input);
-
-    let (_path, cost) = dijkstra(
-        &(0, valley.start),
-        |&(round, pos)| valley.moves(round, &pos),
-        |(_round, pos)| *pos == valley.end,
-    )?;
-
-    Some(cost)
+    valley.timed_grid().trip(0, &[valley.start, valley.end])
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
     let valley = parse_input(input);
-
-    let mut total_cost = 0;
-
-    let (_path, cost) = dijkstra(
-        &(total_cost, valley.start),
-        |&(round, pos)| valley.moves(round, &pos),
-        |(_round, pos)| *pos == valley.end,
-    )?;
-
-    total_cost += cost;
-
-    let (_path, cost) = dijkstra(
-        &(total_cost, valley.end),
-        |&(round, pos)| valley.moves(round, &pos),
-        |(_round, pos)| *pos == valley.start,
-    )?;
-
-    total_cost += cost;
-
-    let (_path, cost) = dijkstra(
-        &(total_cost, valley.start),
-        |&(round, pos)| valley.moves(round, &pos),
-        |(_round, pos)| *pos == valley.end,
-    )?;
-
-    total_cost += cost;
-
-    Some(total_cost)
+    // There, back for the snacks, and there again
+    valley
+        .timed_grid()
+        .trip(0, &[valley.start, valley.end, valley.start, valley.end])
 }
 
 fn main() {
@@ -2,8 +2,10 @@
  * This file contains template code.
  * There is no need to edit this file unless you want to change template functionality.
  */
+use advent_of_code::YEAR;
+use aoc_fetch::Puzzle;
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::Write,
     process,
 };
@@ -29,7 +31,7 @@ mod tests {
     #[test]
     fn test_part_one_example() {
         let input = advent_of_code::read_file("examples", DAY);
-        assert_eq!(part_one(&input), None);
+        assert_eq!(part_one(&input), PART_ONE_ANSWER);
     }
 
     #[test]
@@ -41,7 +43,7 @@ mod tests {
     #[test]
     fn test_part_two_example() {
         let input = advent_of_code::read_file("examples", DAY);
-        assert_eq!(part_two(&input), None);
+        assert_eq!(part_two(&input), PART_TWO_ANSWER);
     }
 
     #[test]
@@ -62,7 +64,38 @@ fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
 }
 
 fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+}
+
+/// The example answer as it goes into the test: `Some(142)` or `None`. The template
+/// returns `Option<u32>`, so answers that aren't numbers are left in a comment for
+/// whoever changes the return type, with anything that would end (or, since block
+/// comments nest, open) a comment broken up.
+fn answer_literal(answer: Option<&str>) -> String {
+    match answer {
+        Some(answer) if answer.parse::<u32>().is_ok() => format!("Some({})", answer),
+        Some(answer) => {
+            let answer = format!("{:?}", answer)
+                .replace("*/", "*\\/")
+                .replace("/*", "/\\*");
+            format!("None /* example answer: {} */", answer)
+        }
+        None => "None".to_string(),
+    }
+}
+
+fn fetch_puzzle(day: u8) -> Puzzle {
+    match aoc_fetch::fetch_puzzle(YEAR, day) {
+        Ok(puzzle) => puzzle,
+        Err(e) => {
+            eprintln!("Couldn't fetch the puzzle description: {:#}", e);
+            Puzzle::default()
+        }
+    }
 }
 
 fn main() {
@@ -88,7 +121,14 @@ fn main() {
         }
     };
 
-    match file.write_all(MODULE_TEMPLATE.replace("DAY", &day.to_string()).as_bytes()) {
+    let puzzle = fetch_puzzle(day);
+    // The answers go in last, so nothing in them is taken for a placeholder
+    let module = MODULE_TEMPLATE
+        .replace("DAY", &day.to_string())
+        .replace("PART_ONE_ANSWER", &answer_literal(puzzle.answer(1)))
+        .replace("PART_TWO_ANSWER", &answer_literal(puzzle.answer(2)));
+
+    match file.write_all(module.as_bytes()) {
         Ok(_) => {
             println!("Created module file \"{}\"", &module_path);
         }
@@ -108,14 +148,25 @@ fn main() {
         }
     }
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to create example file: {}", e);
-            process::exit(1);
-        }
+    match puzzle.example() {
+        Some(example) => match fs::write(&example_path, example) {
+            Ok(_) => {
+                println!("Wrote example to \"{}\"", &example_path);
+            }
+            Err(e) => {
+                eprintln!("Failed to write example file: {}", e);
+                process::exit(1);
+            }
+        },
+        None => match create_file(&example_path) {
+            Ok(_) => {
+                println!("Created empty example file \"{}\"", &example_path);
+            }
+            Err(e) => {
+                eprintln!("Failed to create example file: {}", e);
+                process::exit(1);
+            }
+        },
     }
 
     println!("---");
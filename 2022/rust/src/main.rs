@@ -2,45 +2,112 @@
  * This file contains template code.
  * There is no need to edit this file unless you want to change template functionality.
  */
-use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
-use std::process::Command;
 
-fn main() {
-    let total: f64 = (1..=25)
-        .map(|day| {
-            let day = format!("{:02}", day);
-
-            let cmd = Command::new("cargo")
-                .args(["run", "--release", "--bin", &day])
-                .output()
-                .unwrap();
-
-            println!("----------");
-            println!("{}| Day {} |{}", ANSI_BOLD, day, ANSI_RESET);
-            println!("----------");
+// Every day is linked into this binary so that `cargo all` can run them in-process.
+// The days' own tests already run as part of their bin targets, so skip them here.
+#[cfg(not(test))]
+#[allow(dead_code)]
+#[path = "bin"]
+mod days {
+    #[path = "01.rs"]
+    pub mod day01;
+    #[path = "02.rs"]
+    pub mod day02;
+    #[path = "03.rs"]
+    pub mod day03;
+    #[path = "04.rs"]
+    pub mod day04;
+    #[path = "05.rs"]
+    pub mod day05;
+    #[path = "06.rs"]
+    pub mod day06;
+    #[path = "07.rs"]
+    pub mod day07;
+    #[path = "08.rs"]
+    pub mod day08;
+    #[path = "09.rs"]
+    pub mod day09;
+    #[path = "10.rs"]
+    pub mod day10;
+    #[path = "11.rs"]
+    pub mod day11;
+    #[path = "12.rs"]
+    pub mod day12;
+    #[path = "13.rs"]
+    pub mod day13;
+    #[path = "14.rs"]
+    pub mod day14;
+    #[path = "15.rs"]
+    pub mod day15;
+    #[path = "16.rs"]
+    pub mod day16;
+    #[path = "17.rs"]
+    pub mod day17;
+    #[path = "18.rs"]
+    pub mod day18;
+    #[path = "19.rs"]
+    pub mod day19;
+    #[path = "20.rs"]
+    pub mod day20;
+    #[path = "21.rs"]
+    pub mod day21;
+    #[path = "22.rs"]
+    pub mod day22;
+    #[path = "23.rs"]
+    pub mod day23;
+    #[path = "24.rs"]
+    pub mod day24;
+    #[path = "25.rs"]
+    pub mod day25;
 
-            let output = String::from_utf8(cmd.stdout).unwrap();
-            let is_empty = output.is_empty();
+    use advent_of_code::runner::Solver;
+    use advent_of_code::solver;
 
-            println!(
-                "{}",
-                if is_empty {
-                    "Not solved."
-                } else {
-                    output.trim()
-                }
-            );
+    pub fn solvers() -> Vec<Solver> {
+        vec![
+            solver!(1, day01),
+            solver!(2, day02),
+            solver!(3, day03),
+            solver!(4, day04),
+            solver!(5, day05),
+            solver!(6, day06),
+            solver!(7, day07),
+            solver!(8, day08),
+            solver!(9, day09),
+            solver!(10, day10),
+            solver!(11, day11),
+            solver!(12, day12),
+            solver!(13, day13),
+            solver!(14, day14),
+            solver!(15, |input| day15::part_one(input, 2_000_000), |input| {
+                day15::part_two(input, 4_000_000, 4_000_000)
+            }),
+            solver!(16, day16),
+            solver!(17, day17),
+            solver!(18, day18),
+            solver!(19, day19),
+            solver!(20, day20),
+            solver!(21, day21),
+            solver!(22, day22),
+            solver!(23, day23),
+            solver!(24, day24),
+            solver!(25, day25),
+        ]
+    }
+}
 
-            if is_empty {
-                0_f64
-            } else {
-                advent_of_code::parse_exec_time(&output)
+#[cfg(not(test))]
+fn main() {
+    match advent_of_code::bench::BenchConfig::from_args() {
+        Some(config) => {
+            advent_of_code::bench::bench_all(&days::solvers(), &config);
+        }
+        None => {
+            let record_answers = std::env::args().any(|arg| arg == "--record-answers");
+            let runs = advent_of_code::runner::run_all(&days::solvers(), record_answers);
+            if runs.iter().any(|run| run.has_wrong_answers()) {
+                std::process::exit(1);
             }
-        })
-        .sum();
-
-    println!(
-        "{}Total:{} {}{:.2}ms{}",
-        ANSI_BOLD, ANSI_RESET, ANSI_ITALIC, total, ANSI_RESET
-    );
+        }
+    }
 }
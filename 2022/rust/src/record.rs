@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use crate::answers::{Answers, Verdict};
+use crate::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+/// Environment variable that switches `solve!` output to JSON lines.
+pub const JSON_ENV_VAR: &str = "AOC_JSON";
+
+/// Command line flag that switches `solve!` output to JSON lines.
+pub const JSON_FLAG: &str = "--json";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Solved,
+    Unsolved,
+    Panicked,
+}
+
+/// The outcome of running one part of one day.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub duration_ns: u64,
+    pub status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<Verdict>,
+}
+
+impl Record {
+    /// Runs `func` on `input`, timing it and catching any panic.
+    pub fn capture<T: Display>(
+        day: u8,
+        part: u8,
+        func: impl FnOnce(&str) -> Option<T>,
+        input: &str,
+    ) -> Self {
+        let timer = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| func(input)));
+        let elapsed = timer.elapsed();
+
+        let (answer, status) = match result {
+            Ok(Some(answer)) => (Some(answer.to_string()), Status::Solved),
+            Ok(None) => (None, Status::Unsolved),
+            Err(_) => (None, Status::Panicked),
+        };
+
+        Record {
+            day,
+            part,
+            answer,
+            duration_ns: elapsed.as_nanos() as u64,
+            status,
+            verdict: None,
+        }
+    }
+
+    /// Checks the answer against the recorded answers for the day.
+    pub fn check(mut self, answers: &Answers) -> Self {
+        self.verdict = answers.check(self.part, self.answer.as_deref());
+        self
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.duration_ns)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("record is always serializable")
+    }
+
+    /// Parses a single JSON line. Returns `None` for anything that isn't a record,
+    /// so that stray output from a solution can be skipped.
+    pub fn from_json(line: &str) -> Option<Self> {
+        serde_json::from_str(line.trim()).ok()
+    }
+
+    /// Prints the record in the human-readable format.
+    pub fn print(&self) {
+        let answer = match (&self.answer, self.verdict) {
+            (Some(answer), Some(verdict)) => Some(format!("{} {}", answer, verdict)),
+            (answer, _) => answer.clone(),
+        };
+        let note = format!("elapsed: {:.2?}", self.elapsed());
+        print_part(self.part, self.status, answer.as_deref(), &note);
+    }
+
+    /// Prints the record as JSON if enabled, or in the human-readable format otherwise.
+    pub fn emit(&self) {
+        if json_enabled() {
+            println!("{}", self.to_json());
+        } else {
+            self.print();
+        }
+    }
+}
+
+/// Prints one part in the human-readable format: a header, then the answer with
+/// `note` in italics, or why there is no answer.
+pub fn print_part(part: u8, status: Status, answer: Option<&str>, note: &str) {
+    println!("🎄 {}Part {}{} 🎄", ANSI_BOLD, part, ANSI_RESET);
+    match (status, answer) {
+        (Status::Solved, Some(answer)) => {
+            println!("{} {}({}){}", answer, ANSI_ITALIC, note, ANSI_RESET);
+        }
+        (Status::Panicked, _) => {
+            println!("panicked.")
+        }
+        _ => {
+            println!("not solved.")
+        }
+    }
+}
+
+/// JSON output is enabled by setting `AOC_JSON` (to anything but `0`) or by passing `--json`.
+pub fn json_enabled() -> bool {
+    let from_env = env::var(JSON_ENV_VAR)
+        .map(|v| !v.is_empty() && v != "0")
+        .unwrap_or(false);
+    from_env || env::args().any(|arg| arg == JSON_FLAG)
+}
+
+/// Extracts the day from a binary name such as `07` or `07-flat`.
+pub fn bin_day(bin_name: &str) -> u8 {
+    let digits: String = bin_name
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture() {
+        let record = Record::capture(1, 2, |input: &str| Some(input.len()), "abc");
+        assert_eq!(record.day, 1);
+        assert_eq!(record.part, 2);
+        assert_eq!(record.answer, Some("3".to_string()));
+        assert_eq!(record.status, Status::Solved);
+
+        let record = Record::capture(1, 1, |_: &str| None::<u32>, "");
+        assert_eq!(record.answer, None);
+        assert_eq!(record.status, Status::Unsolved);
+
+        let record = Record::capture(1, 1, |_: &str| -> Option<u32> { panic!("boom") }, "");
+        assert_eq!(record.answer, None);
+        assert_eq!(record.status, Status::Panicked);
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let record = Record {
+            day: 5,
+            part: 1,
+            answer: Some("CMZ".to_string()),
+            duration_ns: 1234,
+            status: Status::Solved,
+            verdict: None,
+        };
+        let json = record.to_json();
+        assert_eq!(
+            json,
+            r#"{"day":5,"part":1,"answer":"CMZ","duration_ns":1234,"status":"solved"}"#
+        );
+        assert_eq!(Record::from_json(&json), Some(record.clone()));
+        assert_eq!(Record::from_json("some debug output"), None);
+
+        let record = record.check(&Answers::default());
+        assert_eq!(
+            record.to_json(),
+            r#"{"day":5,"part":1,"answer":"CMZ","duration_ns":1234,"status":"solved","verdict":"new"}"#
+        );
+        assert_eq!(Record::from_json(&record.to_json()), Some(record));
+    }
+
+    #[test]
+    fn test_bin_day() {
+        assert_eq!(bin_day("01"), 1);
+        assert_eq!(bin_day("07-flat"), 7);
+        assert_eq!(bin_day("25"), 25);
+        assert_eq!(bin_day("scaffold"), 0);
+    }
+}
@@ -0,0 +1,420 @@
+use std::io;
+use std::time::Duration;
+
+use crate::answers::{Answers, Verdict};
+use crate::record::{Record, Status};
+use crate::{try_read_file, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET, YEAR};
+
+/// A solver for one part, with the answer already formatted.
+pub type PartFn = fn(&str) -> Option<String>;
+
+/// Both parts of a day, as registered with the in-process runner.
+pub struct Solver {
+    pub day: u8,
+    pub part_one: PartFn,
+    pub part_two: PartFn,
+}
+
+/// Builds a [`Solver`] from a module exposing `part_one` and `part_two`.
+///
+/// Solutions that need extra arguments can pass closures instead:
+/// `solver!(15, |i| day15::part_one(i, 2_000_000), |i| day15::part_two(i, 4_000_000, 4_000_000))`.
+#[macro_export]
+macro_rules! solver {
+    ($day:expr, $module:ident) => {
+        $crate::solver!($day, $module::part_one, $module::part_two)
+    };
+    ($day:expr, $part_one:expr, $part_two:expr) => {
+        $crate::runner::Solver {
+            day: $day,
+            part_one: |input| ($part_one)(input).map(|answer| answer.to_string()),
+            part_two: |input| ($part_two)(input).map(|answer| answer.to_string()),
+        }
+    };
+}
+
+/// The records for one day. `records` is empty if there is no input for the day, and
+/// they are left unchecked if the recorded answers couldn't be read.
+pub struct DayRun {
+    pub day: u8,
+    pub records: Vec<Record>,
+    pub answers: io::Result<Answers>,
+}
+
+impl DayRun {
+    pub fn has_wrong_answers(&self) -> bool {
+        self.records
+            .iter()
+            .any(|r| r.verdict == Some(Verdict::Wrong))
+    }
+
+    /// Stores answers for parts that don't have a recorded answer yet.
+    /// Existing answers are never overwritten, and neither is an unreadable answers file.
+    pub fn record_new_answers(&mut self) -> io::Result<bool> {
+        let answers = match &mut self.answers {
+            Ok(answers) => answers,
+            Err(e) => return Err(io::Error::new(e.kind(), format!("not overwriting {}", e))),
+        };
+        let mut changed = false;
+        for record in &self.records {
+            if let (Some(Verdict::New), Some(answer)) = (record.verdict, &record.answer) {
+                answers.set(record.part, answer);
+                changed = true;
+            }
+        }
+        if changed {
+            answers.save(YEAR, self.day)?;
+        }
+        Ok(changed)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.records.iter().map(Record::elapsed).sum()
+    }
+
+    fn part(&self, part: u8) -> Option<&Record> {
+        self.records.iter().find(|r| r.part == part)
+    }
+}
+
+impl Solver {
+    /// Runs both parts against `input`, catching panics per part.
+    pub fn run_with_input(&self, input: &str) -> Vec<Record> {
+        vec![
+            Record::capture(self.day, 1, self.part_one, input),
+            Record::capture(self.day, 2, self.part_two, input),
+        ]
+    }
+
+    /// Runs both parts against the day's input file, if there is one, and checks
+    /// the results against the recorded answers.
+    pub fn run(&self) -> DayRun {
+        let answers = Answers::load(YEAR, self.day);
+        let records = match try_read_file("inputs", self.day) {
+            Some(input) => self
+                .run_with_input(&input)
+                .into_iter()
+                .map(|r| match &answers {
+                    Ok(answers) => r.check(answers),
+                    Err(_) => r,
+                })
+                .collect(),
+            None => vec![],
+        };
+        DayRun {
+            day: self.day,
+            records,
+            answers,
+        }
+    }
+}
+
+fn answer_cell(run: &DayRun, part: u8) -> String {
+    let record = match run.part(part) {
+        None => return "no input".to_string(),
+        Some(record) => record,
+    };
+    let answer = match (&record.status, &record.answer) {
+        (Status::Solved, Some(answer)) if answer.contains('\n') => "(see below)".to_string(),
+        (Status::Solved, Some(answer)) => answer.clone(),
+        (Status::Panicked, _) => "panicked".to_string(),
+        _ => "not solved".to_string(),
+    };
+    let expected = run
+        .answers
+        .as_ref()
+        .ok()
+        .and_then(|answers| answers.get(part));
+    match (record.verdict, expected) {
+        (Some(Verdict::Wrong), Some(expected)) if !expected.contains('\n') => {
+            format!("{} {} (expected {})", answer, Verdict::Wrong, expected)
+        }
+        (Some(verdict), _) => format!("{} {}", answer, verdict),
+        (None, _) => answer,
+    }
+}
+
+fn time_cell(record: Option<&Record>) -> String {
+    match record {
+        Some(record) => format!("{:.2?}", record.elapsed()),
+        None => "-".to_string(),
+    }
+}
+
+fn part_total(runs: &[DayRun], part: u8) -> Duration {
+    runs.iter()
+        .filter_map(|run| run.part(part))
+        .map(Record::elapsed)
+        .sum()
+}
+
+/// Terminal width of `s`, counting the emoji used for verdicts as two columns.
+fn display_width(s: &str) -> usize {
+    s.chars()
+        .map(|c| match c {
+            '✅' | '❌' | '\u{1F300}'.. => 2,
+            // ⚠️ is ⚠ followed by a variation selector, so it already counts as two
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Lays out `sections` of rows as a markdown-style table, with a separator line
+/// between sections. Columns listed in `right_aligned` are right-aligned.
+pub(crate) fn format_table(sections: &[Vec<Vec<String>>], right_aligned: &[usize]) -> String {
+    let columns = sections
+        .iter()
+        .flatten()
+        .map(|row| row.len())
+        .max()
+        .unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|col| {
+            sections
+                .iter()
+                .flatten()
+                .filter_map(|row| row.get(col))
+                .map(|cell| display_width(cell))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |row: &Vec<String>| {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(col, &width)| {
+                let cell = row.get(col).map(String::as_str).unwrap_or("");
+                let padding = " ".repeat(width - display_width(cell));
+                if right_aligned.contains(&col) {
+                    padding + cell
+                } else {
+                    cell.to_string() + &padding
+                }
+            })
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let separator = format!(
+        "|{}|\n",
+        widths
+            .iter()
+            .map(|w| "-".repeat(w + 2))
+            .collect::<Vec<_>>()
+            .join("|")
+    );
+
+    sections
+        .iter()
+        .map(|rows| rows.iter().map(format_row).collect::<String>())
+        .collect::<Vec<_>>()
+        .join(&separator)
+}
+
+/// Renders the results of a run as a table with per-part times and totals.
+pub fn render_table(runs: &[DayRun]) -> String {
+    let rows: Vec<Vec<String>> = runs
+        .iter()
+        .map(|run| {
+            vec![
+                format!("{:02}", run.day),
+                answer_cell(run, 1),
+                time_cell(run.part(1)),
+                answer_cell(run, 2),
+                time_cell(run.part(2)),
+            ]
+        })
+        .collect();
+
+    let header = ["Day", "Part 1", "Time", "Part 2", "Time"]
+        .map(String::from)
+        .to_vec();
+    let total = vec![
+        "Total".to_string(),
+        String::new(),
+        format!("{:.2?}", part_total(runs, 1)),
+        String::new(),
+        format!("{:.2?}", part_total(runs, 2)),
+    ];
+
+    let mut s = format_table(&[vec![header], rows, vec![total]], &[2, 4]);
+
+    // Answers spanning several lines (e.g. rendered letters) don't fit in a cell
+    for record in runs.iter().flat_map(|run| &run.records) {
+        if let Some(answer) = record.answer.as_ref().filter(|a| a.contains('\n')) {
+            s += &format!(
+                "\nDay {:02} part {}:\n{}\n",
+                record.day, record.part, answer
+            );
+        }
+    }
+    s
+}
+
+/// Runs every solver in-process and prints a table of the results, or JSON lines
+/// if JSON output is enabled. With `record_answers`, answers for parts without a
+/// recorded answer are saved to the answers store.
+pub fn run_all(solvers: &[Solver], record_answers: bool) -> Vec<DayRun> {
+    let json = crate::record::json_enabled();
+    let runs: Vec<DayRun> = solvers
+        .iter()
+        .map(|solver| {
+            let mut run = solver.run();
+            if let Err(e) = &run.answers {
+                eprintln!(
+                    "Couldn't read the recorded answers for day {:02}: {}",
+                    run.day, e
+                );
+            }
+            if json {
+                run.records.iter().for_each(Record::emit);
+            }
+            if record_answers {
+                match run.record_new_answers() {
+                    Ok(true) => eprintln!(
+                        "Recorded new answers in \"{}\"",
+                        Answers::path(YEAR, run.day).display()
+                    ),
+                    Ok(false) => {}
+                    Err(e) => eprintln!("Failed to record answers: {}", e),
+                }
+            }
+            run
+        })
+        .collect();
+
+    if !json {
+        println!("{}", render_table(&runs));
+        let total: Duration = runs.iter().map(DayRun::elapsed).sum();
+        println!(
+            "{}Total:{} {}{:.2}ms{}",
+            ANSI_BOLD,
+            ANSI_RESET,
+            ANSI_ITALIC,
+            total.as_secs_f64() * 1000_f64,
+            ANSI_RESET
+        );
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_solver() -> Solver {
+        Solver {
+            day: 3,
+            part_one: |input| Some(input.len().to_string()),
+            part_two: |_| panic!("not yet"),
+        }
+    }
+
+    #[test]
+    fn test_run_catches_panics() {
+        let records = example_solver().run_with_input("abcd");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].answer, Some("4".to_string()));
+        assert_eq!(records[0].status, Status::Solved);
+        assert_eq!(records[1].status, Status::Panicked);
+    }
+
+    #[test]
+    fn test_render_table() {
+        let mut records = example_solver().run_with_input("abcd");
+        for r in records.iter_mut() {
+            r.duration_ns = 1_500_000;
+        }
+        let runs = vec![
+            DayRun {
+                day: 3,
+                records,
+                answers: Ok(Answers::default()),
+            },
+            DayRun {
+                day: 4,
+                records: vec![],
+                answers: Ok(Answers::default()),
+            },
+        ];
+        let table = render_table(&runs);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[0],
+            "| Day   | Part 1   |   Time | Part 2   |   Time |"
+        );
+        assert_eq!(
+            lines[2],
+            "| 03    | 4        | 1.50ms | panicked | 1.50ms |"
+        );
+        assert_eq!(
+            lines[3],
+            "| 04    | no input |      - | no input |      - |"
+        );
+        assert_eq!(
+            lines[5],
+            "| Total |          | 1.50ms |          | 1.50ms |"
+        );
+    }
+
+    #[test]
+    fn test_verdicts_in_table() {
+        let answers = Answers {
+            part_one: Some("5".to_string()),
+            part_two: None,
+        };
+        let records = example_solver()
+            .run_with_input("abcd")
+            .into_iter()
+            .map(|r| r.check(&answers))
+            .collect();
+        let run = DayRun {
+            day: 3,
+            records,
+            answers: Ok(answers),
+        };
+        assert!(run.has_wrong_answers());
+        assert_eq!(answer_cell(&run, 1), "4 ❌ (expected 5)");
+        assert_eq!(answer_cell(&run, 2), "panicked");
+
+        let answers = Answers {
+            part_one: Some("4".to_string()),
+            part_two: None,
+        };
+        let records = vec![Record::capture(3, 1, |_: &str| Some(4), "").check(&answers)];
+        let run = DayRun {
+            day: 3,
+            records,
+            answers: Ok(Answers::default()),
+        };
+        assert!(!run.has_wrong_answers());
+        assert_eq!(answer_cell(&run, 1), "4 ✅");
+    }
+
+    #[test]
+    fn test_unreadable_answers_are_not_overwritten() {
+        let records = vec![Record::capture(3, 1, |_: &str| Some(4), "")];
+        let mut run = DayRun {
+            day: 3,
+            records,
+            answers: Err(io::Error::new(io::ErrorKind::InvalidData, "bad toml")),
+        };
+        assert!(run.record_new_answers().is_err());
+        assert_eq!(answer_cell(&run, 1), "4");
+    }
+
+    #[test]
+    fn test_solver_macro() {
+        fn part_one(input: &str) -> Option<u32> {
+            Some(input.len() as u32)
+        }
+        fn part_two(_input: &str) -> Option<String> {
+            None
+        }
+        let solver = solver!(1, part_one, part_two);
+        assert_eq!((solver.part_one)("ab"), Some("2".to_string()));
+        assert_eq!((solver.part_two)("ab"), None);
+    }
+}
@@ -1,66 +1,210 @@
-use chrono::Datelike;
-use std::{env, fs, io, path::PathBuf};
+use anyhow::{Context, Result, bail};
+use aoc::puzzle::{self, Puzzle};
+use aoc::submit::AnswerLog;
+use aoc::{CachedSource, HttpSource, InputSource};
+use chrono::Utc;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
-fn main() -> io::Result<()> {
-    // DAY: from first CLI arg or from today's date
-    let day: u8 = env::args()
-        .nth(1)
-        .map(|s| s.parse().expect("day must be a number"))
-        .unwrap_or_else(today_day);
+const USAGE: &str = "usage: new_day [--year <year>] [--day <day> | <day>] [--fetch]";
 
-    let file_name = format!("{day:02}.rs");
-    let path: PathBuf = ["src", "bin", &file_name].iter().collect();
+/// Templates live in `templates/` in the project; any that are missing there fall
+/// back to the ones this binary was built with. Other years' projects only get
+/// scaffolded if they have a `templates/` directory, since the files are laid out the
+/// way this project lays them out.
+const TEMPLATE_DIR: &str = "templates";
+const DEFAULT_TEMPLATES: &[(&str, &str)] = &[
+    ("day.rs.tmpl", include_str!("../../templates/day.rs.tmpl")),
+    (
+        "example.txt.tmpl",
+        include_str!("../../templates/example.txt.tmpl"),
+    ),
+    (
+        "answers.txt.tmpl",
+        include_str!("../../templates/answers.txt.tmpl"),
+    ),
+];
 
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    year: Option<u16>,
+    day: Option<u8>,
+    fetch: bool,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args> {
+    let mut parsed = Args {
+        year: None,
+        day: None,
+        fetch: false,
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" | "-y" => {
+                let year = args.next().context(USAGE)?;
+                parsed.year = Some(year.parse().context("year must be a number")?);
+            }
+            "--day" | "-d" => {
+                let day = args.next().context(USAGE)?;
+                parsed.day = Some(day.parse().context("day must be a number")?);
+            }
+            "--fetch" => parsed.fetch = true,
+            day if parsed.day.is_none() && !day.starts_with('-') => {
+                parsed.day = Some(day.parse().context("day must be a number")?);
+            }
+            _ => bail!(USAGE),
+        }
+    }
+    Ok(parsed)
+}
+
+/// The Rust project for `year`: this one, or its `{year}/rust` sibling.
+fn project_dir(year: u16) -> Result<PathBuf> {
+    let this = Path::new(env!("CARGO_MANIFEST_DIR"));
+    if aoc::crate_year() == Some(year) {
+        return Ok(this.to_path_buf());
+    }
+    let root = this.ancestors().nth(2).context("no repository root")?;
+    let dir = root.join(year.to_string()).join("rust");
+    if !dir.join("Cargo.toml").exists() {
+        bail!("there is no Rust project for {year} at {}", dir.display());
+    }
+    if !dir.join(TEMPLATE_DIR).is_dir() {
+        bail!(
+            "{} has no {TEMPLATE_DIR}/ directory, so it isn't laid out like this project; \
+             use its own scaffolding instead",
+            dir.display()
+        );
+    }
+    Ok(dir)
+}
+
+fn load_template(project: &Path, name: &str) -> Result<String> {
+    let path = project.join(TEMPLATE_DIR).join(name);
     if path.exists() {
-        eprintln!("{} already exists, refusing to overwrite", path.display());
-        return Ok(());
+        return fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read template {}", path.display()));
     }
+    DEFAULT_TEMPLATES
+        .iter()
+        .find(|(default, _)| *default == name)
+        .map(|(_, template)| template.to_string())
+        .with_context(|| format!("no template named {name}"))
+}
 
-    let template = day_template();
+/// The example answer as it goes into the test. The template's parts return `u32`, so
+/// answers that aren't numbers are left in a comment for whoever changes the type,
+/// with anything that would end (or, since block comments nest, open) it broken up.
+fn answer_literal(answer: Option<&str>) -> String {
+    match answer {
+        Some(answer) if answer.parse::<u32>().is_ok() => answer.to_string(),
+        Some(answer) => {
+            let answer = format!("{answer:?}")
+                .replace("*/", "*\\/")
+                .replace("/*", "/\\*");
+            format!("0 /* example answer: {answer} */")
+        }
+        None => "0".to_string(),
+    }
+}
 
-    fs::write(&path, template)?;
+/// Replaces `{{YEAR}}`, `{{DAY}}`, `{{DD}}` (the zero-padded day), `{{EXAMPLE}}`,
+/// `{{PART1_ANSWER}}` and `{{PART2_ANSWER}}`.
+fn fill_template(template: &str, year: u16, day: u8, puzzle: &Puzzle) -> String {
+    template
+        .replace("{{YEAR}}", &year.to_string())
+        .replace("{{DAY}}", &day.to_string())
+        .replace("{{DD}}", &format!("{day:02}"))
+        .replace("{{EXAMPLE}}", puzzle.example().unwrap_or(""))
+        .replace("{{PART1_ANSWER}}", &answer_literal(puzzle.answer(1)))
+        .replace("{{PART2_ANSWER}}", &answer_literal(puzzle.answer(2)))
+}
 
+/// Writes a file unless it already exists, so re-running never clobbers work.
+fn create(path: &Path, contents: &str) -> Result<()> {
+    if path.exists() {
+        println!("{} already exists, leaving it alone", path.display());
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))?;
     println!("Created {}", path.display());
     Ok(())
 }
 
-fn today_day() -> u8 {
-    let now = chrono::Local::now();
+fn main() -> Result<()> {
+    let args = parse_args(env::args().skip(1))?;
+    let today = puzzle::today(Utc::now());
+
+    let year = match args.year {
+        Some(year) => year,
+        None => aoc::crate_year().context("couldn't tell the year, pass --year")?,
+    };
+    let day = match (args.day, today) {
+        (Some(day), _) => day,
+        (None, Some((today_year, today_day))) if today_year == year => today_day,
+        (None, _) => bail!("there's no puzzle today, pass --day\n{USAGE}"),
+    };
+    if !(1..=puzzle::last_day(year)).contains(&day) {
+        bail!("{year} only has days 1 to {}", puzzle::last_day(year));
+    }
 
-    // AoC is in December
-    if now.month() != 12 {
-        panic!(
-            "today_day() only works in December; got month {}",
-            now.month()
+    let unlock = puzzle::unlock_time(year, day);
+    let unlocked = Utc::now() >= unlock;
+    if !unlocked {
+        eprintln!(
+            "⚠️ {year} day {day} unlocks at {} ({} UTC), scaffolding without the puzzle",
+            unlock.format("%Y-%m-%d %H:%M %:z"),
+            unlock.with_timezone(&Utc).format("%H:%M"),
         );
     }
 
-    let day = now.day();
+    let project = project_dir(year)?;
+    aoc::load_env();
 
-    if day >= 1 && day <= 25 {
-        day as u8
+    let puzzle = if unlocked {
+        HttpSource::from_env()
+            .fetch_puzzle(year, day)
+            .map(|html| Puzzle::parse(&html))
+            .unwrap_or_else(|e| {
+                eprintln!(
+                    "Couldn't fetch the puzzle description, leaving the example empty: {e:#}"
+                );
+                Puzzle::default()
+            })
     } else {
-        panic!("today_day() only works for Dec 1–25; got day {}", day);
-    }
-}
+        Puzzle::default()
+    };
 
-// keep template in code so it’s easy to edit
-fn day_template() -> String {
-    r##"use aoc::todays_input;
-use anyhow::Result;
-
-fn part1(input: &str) -> u32 {
-    todo!();
-}
+    let cache_dir = project.join(aoc::input::cache_dir());
+    let files = [
+        ("day.rs.tmpl", project.join(format!("src/bin/{day:02}.rs"))),
+        (
+            "example.txt.tmpl",
+            project.join(format!("examples/{year}-{day:02}.txt")),
+        ),
+        ("answers.txt.tmpl", AnswerLog::path(&cache_dir, year, day)),
+    ];
+    for (template, path) in files {
+        let template = load_template(&project, template)?;
+        create(&path, &fill_template(&template, year, day, &puzzle))?;
+    }
 
-fn part2(input: &str) -> u32 {
-    todo!();
-}
+    if args.fetch {
+        if unlocked {
+            let source = CachedSource::new(&cache_dir, HttpSource::from_env());
+            source.fetch(year, day)?;
+            println!("Fetched input into {}", source.path(year, day).display());
+        } else {
+            eprintln!("Not fetching the input before the puzzle unlocks");
+        }
+    }
 
-fn main() -> Result<()> {
-    let input = todays_input!()?;
-    dbg!(part1(&input));
-    dbg!(part2(&input));
     Ok(())
 }
 
@@ -68,37 +212,75 @@ fn main() -> Result<()> {
 mod tests {
     use super::*;
 
-    const PART1_EXAMPLE: &str = r#"
-
-    "#;
+    fn args(args: &str) -> Result<Args> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
 
     #[test]
-    fn test_part1_example() {
-        assert_eq!(0, part1(PART1_EXAMPLE));
+    fn test_parse_args() {
+        assert_eq!(
+            args("7").unwrap(),
+            Args {
+                year: None,
+                day: Some(7),
+                fetch: false
+            }
+        );
+        assert_eq!(
+            args("--year 2024 --day 3 --fetch").unwrap(),
+            Args {
+                year: Some(2024),
+                day: Some(3),
+                fetch: true
+            }
+        );
+        assert!(args("--day").is_err());
+        assert!(args("--day 3 4").is_err());
+        assert!(args("--bogus").is_err());
     }
 
     #[test]
-    #[ignore]
-    fn test_part1() -> Result<()> {
-        let input = aoc::todays_input!()?;
-        assert_eq!(0, part1(&input));
-        Ok(())
+    fn test_fill_template() {
+        let puzzle = Puzzle {
+            examples: vec!["1\n2\n".to_string()],
+            answers: vec![Some("142".to_string()), Some("CMZ".to_string())],
+        };
+        let template = "{{YEAR}}/{{DD}} ({{DAY}}): {{PART1_ANSWER}} {{PART2_ANSWER}}\n{{EXAMPLE}}";
+        assert_eq!(
+            fill_template(template, 2025, 4, &puzzle),
+            "2025/04 (4): 142 0 /* example answer: \"CMZ\" */\n1\n2\n"
+        );
+        let puzzle = Puzzle {
+            examples: vec![],
+            answers: vec![Some("*/{{DAY}}".to_string()), Some("/*".to_string())],
+        };
+        assert_eq!(
+            fill_template(template, 2025, 4, &puzzle),
+            "2025/04 (4): 0 /* example answer: \"*\\/{{DAY}}\" */ 0 /* example answer: \"/\\*\" */\n"
+        );
+        assert_eq!(
+            fill_template(template, 2025, 4, &Puzzle::default()),
+            "2025/04 (4): 0 0\n"
+        );
     }
 
     #[test]
-    #[ignore]
-    fn test_part2_example() {
-        assert_eq!(0, part2(PART1_EXAMPLE));
+    fn test_project_dir() {
+        assert_eq!(
+            project_dir(aoc::crate_year().unwrap()).unwrap(),
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+        );
+        // 2022 has a Rust project, but one with its own layout and scaffolder
+        let err = project_dir(2022).unwrap_err();
+        assert!(err.to_string().contains("templates/"));
+        assert!(project_dir(1999).is_err());
     }
 
     #[test]
-    #[ignore]
-    fn test_part2() -> Result<()> {
-        let input = aoc::todays_input!()?;
-        assert_eq!(0, part2(&input));
-        Ok(())
+    fn test_default_templates() {
+        for (name, _) in DEFAULT_TEMPLATES {
+            assert!(load_template(Path::new("/nonexistent"), name).is_ok());
+        }
+        assert!(load_template(Path::new("/nonexistent"), "missing.tmpl").is_err());
     }
 }
-"##
-    .to_string()
-}
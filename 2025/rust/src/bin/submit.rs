@@ -0,0 +1,46 @@
+use anyhow::{Context, Result, bail};
+use aoc::{Refusal, SubmitResult};
+use std::env;
+
+fn main() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (day, part, answer) = match &args[..] {
+        [day, part, answer, ..] => (day, part, answer),
+        _ => bail!("usage: submit <day> <part> <answer> [year]"),
+    };
+
+    let day: u8 = day.parse().context("day must be a number")?;
+    let part: u8 = part.parse().context("part must be 1 or 2")?;
+    if part != 1 && part != 2 {
+        bail!("part must be 1 or 2");
+    }
+    let year: u16 = match args.get(3) {
+        Some(year) => year.parse().context("year must be a number")?,
+        None => aoc::crate_year().context("couldn't tell the year, pass it after the answer")?,
+    };
+
+    println!("Submitting {answer} for {year} day {day} part {part}");
+
+    let result = match aoc::submit(year, day, part, answer) {
+        Ok(result) => result,
+        Err(e) => match e.downcast_ref::<Refusal>() {
+            Some(refusal) => {
+                println!("🛑 Not submitting: {refusal}");
+                return Ok(());
+            }
+            None => return Err(e),
+        },
+    };
+
+    match result {
+        SubmitResult::Correct => println!("⭐ That's the right answer!"),
+        SubmitResult::TooHigh => println!("❌ Too high"),
+        SubmitResult::TooLow => println!("❌ Too low"),
+        SubmitResult::Wrong => println!("❌ That's not the right answer"),
+        SubmitResult::RateLimited(wait) => {
+            println!("⏳ Submitted too recently, wait {}s", wait.as_secs())
+        }
+        SubmitResult::AlreadySolved => println!("This part is already solved"),
+    }
+    Ok(())
+}
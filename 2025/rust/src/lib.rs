@@ -1,9 +1,15 @@
-use anyhow::{Context, Result};
-use dotenvy::dotenv;
-use std::{fs, path::PathBuf};
-
-pub fn input_path(year: u16, day: u8) -> PathBuf {
-    format!("inputs/{}-{:02}.txt", year, day).into()
+use std::path::Path;
+
+pub use aoc_fetch::*;
+
+/// The year this crate is for, from the name of the directory it lives in (`2025/rust`).
+pub fn crate_year() -> Option<u16> {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()?
+        .file_name()?
+        .to_str()?
+        .parse()
+        .ok()
 }
 
 pub fn date_from_path(path: &str) -> Option<(u16, u8)> {
@@ -27,36 +33,3 @@ macro_rules! todays_input {
         $crate::fetch_input(year, day)
     }};
 }
-
-pub fn fetch_input(year: u16, day: u8) -> Result<String> {
-    let _ = dotenv();
-
-    let session =
-        std::env::var("AOC_SESSION").context("Missing AOC_SESSION in environment (add to .env)")?;
-
-    let path = input_path(year, day);
-
-    if let Ok(contents) = fs::read_to_string(&path) {
-        return Ok(contents);
-    }
-
-    let url = format!("https://adventofcode.com/{year}/day/{day}/input");
-
-    let client = reqwest::blocking::Client::new();
-    let response = client
-        .get(&url)
-        .header("Cookie", format!("session={session}"))
-        .send()
-        .context("Failed to send request")?
-        .error_for_status()
-        .context("AoC server returned an error")?;
-
-    let body = response.text().context("Failed reading AoC response")?;
-
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
-    }
-    fs::write(&path, &body)?;
-
-    Ok(body)
-}
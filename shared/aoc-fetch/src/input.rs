@@ -0,0 +1,294 @@
+use anyhow::{Context, Result, bail};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_USER_AGENT: &str = "github.com/catlee/adventofcode";
+pub const DEFAULT_CACHE_DIR: &str = "inputs";
+
+/// Somewhere puzzle inputs can be loaded from.
+pub trait InputSource {
+    fn fetch(&self, year: u16, day: u8) -> Result<String>;
+}
+
+pub fn input_file_name(year: u16, day: u8) -> String {
+    format!("{}-{:02}.txt", year, day)
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it into place,
+/// so a failed download never leaves a truncated file behind.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Downloads inputs from the AoC website (or anything that looks like it).
+pub struct HttpSource {
+    pub base_url: String,
+    pub user_agent: String,
+    pub session: Option<String>,
+}
+
+impl HttpSource {
+    pub fn new(session: impl Into<String>) -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            session: Some(session.into()),
+        }
+    }
+
+    /// Reads `AOC_SESSION`, `AOC_BASE_URL` and `AOC_USER_AGENT`. A missing session is
+    /// only an error once something actually needs to be downloaded.
+    pub fn from_env() -> Self {
+        Self {
+            base_url: env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string()),
+            user_agent: env::var("AOC_USER_AGENT")
+                .unwrap_or_else(|_| DEFAULT_USER_AGENT.to_string()),
+            session: env::var("AOC_SESSION").ok(),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn session(&self) -> Result<&str> {
+        self.session
+            .as_deref()
+            .context("Missing AOC_SESSION in environment (add to .env)")
+    }
+
+    pub fn client(&self) -> Result<reqwest::blocking::Client> {
+        reqwest::blocking::Client::builder()
+            .user_agent(&self.user_agent)
+            .build()
+            .context("Failed to build HTTP client")
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url.trim_end_matches('/'), path)
+    }
+}
+
+impl InputSource for HttpSource {
+    fn fetch(&self, year: u16, day: u8) -> Result<String> {
+        let session = self.session()?;
+        let url = self.url(&format!("/{year}/day/{day}/input"));
+
+        let response = self
+            .client()?
+            .get(&url)
+            .header("Cookie", format!("session={session}"))
+            .send()
+            .context("Failed to send request")?
+            .error_for_status()
+            .context("AoC server returned an error")?;
+
+        response.text().context("Failed reading AoC response")
+    }
+}
+
+/// Reads inputs from a directory of `{year}-{day}.txt` files and never touches the network.
+pub struct FixtureDir {
+    pub dir: PathBuf,
+}
+
+impl FixtureDir {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl InputSource for FixtureDir {
+    fn fetch(&self, year: u16, day: u8) -> Result<String> {
+        let path = self.dir.join(input_file_name(year, day));
+        match fs::read_to_string(&path) {
+            Ok(contents) => Ok(contents),
+            Err(e) => bail!("No fixture input at {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Serves inputs from a cache directory, falling back to `inner` and caching what it returns.
+pub struct CachedSource<S> {
+    pub cache_dir: PathBuf,
+    pub inner: S,
+}
+
+impl<S: InputSource> CachedSource<S> {
+    pub fn new(cache_dir: impl Into<PathBuf>, inner: S) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            inner,
+        }
+    }
+
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.cache_dir.join(input_file_name(year, day))
+    }
+}
+
+impl<S: InputSource> InputSource for CachedSource<S> {
+    fn fetch(&self, year: u16, day: u8) -> Result<String> {
+        let path = self.path(year, day);
+
+        if let Ok(contents) = fs::read_to_string(&path) {
+            return Ok(contents);
+        }
+
+        let body = self.inner.fetch(year, day)?;
+        write_atomic(&path, &body)
+            .with_context(|| format!("Failed to cache input at {}", path.display()))?;
+        Ok(body)
+    }
+}
+
+/// The input cache directory: `AOC_CACHE_DIR`, or `inputs/` by default.
+pub fn cache_dir() -> PathBuf {
+    env::var("AOC_CACHE_DIR")
+        .unwrap_or_else(|_| DEFAULT_CACHE_DIR.to_string())
+        .into()
+}
+
+/// The source `fetch_input` uses: inputs from `AOC_FIXTURES_DIR` if it is set, otherwise
+/// the [`cache_dir`] in front of [`HttpSource::from_env`].
+pub fn default_source() -> Box<dyn InputSource> {
+    if let Ok(dir) = env::var("AOC_FIXTURES_DIR") {
+        return Box::new(FixtureDir::new(dir));
+    }
+    Box::new(CachedSource::new(cache_dir(), HttpSource::from_env()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    /// Serves a single HTTP request with `status` and `body`, and hands the raw
+    /// request (start line, headers and body) back through the returned receiver.
+    pub(crate) fn stub_server(status: &str, body: &str) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse().unwrap();
+                }
+                request += &line;
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; content_length];
+            std::io::Read::read_exact(&mut reader, &mut body).unwrap();
+            request += &String::from_utf8(body).unwrap();
+
+            stream.write_all(response.as_bytes()).unwrap();
+            tx.send(request).unwrap();
+        });
+
+        (url, rx)
+    }
+
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_http_source() {
+        let (url, requests) = stub_server("200 OK", "1\n2\n3\n");
+        let source = HttpSource::new("abc")
+            .with_base_url(url)
+            .with_user_agent("test-agent");
+
+        assert_eq!(source.fetch(2025, 3).unwrap(), "1\n2\n3\n");
+
+        let request = requests.recv().unwrap().to_lowercase();
+        assert!(request.starts_with("get /2025/day/3/input http/1.1"));
+        assert!(request.contains("cookie: session=abc"));
+        assert!(request.contains("user-agent: test-agent"));
+    }
+
+    #[test]
+    fn test_http_source_error() {
+        let (url, _) = stub_server("404 Not Found", "not yet");
+        let source = HttpSource::new("abc").with_base_url(url);
+        assert!(source.fetch(2025, 25).is_err());
+    }
+
+    #[test]
+    fn test_http_source_needs_session() {
+        let source = HttpSource {
+            session: None,
+            ..HttpSource::new("")
+        };
+        let err = source.fetch(2025, 1).unwrap_err();
+        assert!(err.to_string().contains("AOC_SESSION"));
+    }
+
+    #[test]
+    fn test_fixture_dir() {
+        let dir = temp_dir("fixtures");
+        fs::write(dir.join("2025-01.txt"), "L68\n").unwrap();
+
+        let source = FixtureDir::new(&dir);
+        assert_eq!(source.fetch(2025, 1).unwrap(), "L68\n");
+        assert!(source.fetch(2025, 2).is_err());
+    }
+
+    #[test]
+    fn test_cached_source() {
+        let fixtures = temp_dir("cache-fixtures");
+        let cache = temp_dir("cache");
+        fs::write(fixtures.join("2025-04.txt"), "@@.\n").unwrap();
+
+        let source = CachedSource::new(cache.join("nested"), FixtureDir::new(&fixtures));
+        assert_eq!(source.fetch(2025, 4).unwrap(), "@@.\n");
+        assert_eq!(
+            fs::read_to_string(cache.join("nested/2025-04.txt")).unwrap(),
+            "@@.\n"
+        );
+
+        // Served from the cache from now on
+        fs::remove_file(fixtures.join("2025-04.txt")).unwrap();
+        assert_eq!(source.fetch(2025, 4).unwrap(), "@@.\n");
+        assert!(source.fetch(2025, 5).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+//! Talking to adventofcode.com: downloading inputs and puzzle pages, and submitting
+//! answers. Shared by every year's Rust project.
+use anyhow::Result;
+use dotenvy::dotenv;
+
+pub mod input;
+pub mod puzzle;
+pub mod submit;
+
+pub use input::{CachedSource, FixtureDir, HttpSource, InputSource};
+pub use puzzle::Puzzle;
+pub use submit::{Refusal, SubmitResult};
+
+/// Loads `.env` (from the working directory or any parent) into the environment,
+/// which is where `AOC_SESSION` usually lives.
+pub fn load_env() {
+    let _ = dotenv();
+}
+
+pub fn fetch_input(year: u16, day: u8) -> Result<String> {
+    load_env();
+    input::default_source().fetch(year, day)
+}
+
+/// Fetches and parses the puzzle description page, for its examples and example answers.
+pub fn fetch_puzzle(year: u16, day: u8) -> Result<Puzzle> {
+    load_env();
+    let html = HttpSource::from_env().fetch_puzzle(year, day)?;
+    Ok(Puzzle::parse(&html))
+}
+
+pub fn submit(
+    year: u16,
+    day: u8,
+    part: u8,
+    answer: impl std::fmt::Display,
+) -> Result<SubmitResult> {
+    load_env();
+    submit::submit_with(
+        &HttpSource::from_env(),
+        &input::cache_dir(),
+        year,
+        day,
+        part,
+        &answer.to_string(),
+    )
+}
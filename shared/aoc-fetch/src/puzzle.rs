@@ -0,0 +1,201 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, FixedOffset, TimeZone};
+
+use crate::input::HttpSource;
+
+/// Puzzles unlock at midnight US Eastern time, which is UTC-5 in December.
+pub fn aoc_timezone() -> FixedOffset {
+    FixedOffset::west_opt(5 * 3600).unwrap()
+}
+
+/// The last day with a puzzle: 25 until 2024, 12 from 2025 on.
+pub fn last_day(year: u16) -> u8 {
+    if year >= 2025 { 12 } else { 25 }
+}
+
+/// When a day's puzzle (and input) becomes available.
+pub fn unlock_time(year: u16, day: u8) -> DateTime<FixedOffset> {
+    aoc_timezone()
+        .with_ymd_and_hms(year.into(), 12, day.into(), 0, 0, 0)
+        .unwrap()
+}
+
+/// The day whose puzzle was released most recently, if that was today in AoC's timezone.
+pub fn today(now: DateTime<impl TimeZone>) -> Option<(u16, u8)> {
+    let now = now.with_timezone(&aoc_timezone()).date_naive();
+    let year = u16::try_from(now.year()).ok()?;
+    let day = u8::try_from(now.day()).ok()?;
+    (now.month() == 12 && (1..=last_day(year)).contains(&day)).then_some((year, day))
+}
+
+/// The bits of a puzzle description page that are useful when scaffolding a day.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    /// The contents of every `<pre><code>` block, in page order.
+    pub examples: Vec<String>,
+    /// The expected example answer for each part that is visible on the page.
+    pub answers: Vec<Option<String>>,
+}
+
+impl Puzzle {
+    pub fn parse(html: &str) -> Self {
+        let examples = between(html, "<pre><code>", "</code></pre>")
+            .map(text)
+            .collect();
+        let answers = between(html, "<article", "</article>")
+            .map(expected_answer)
+            .collect();
+        Self { examples, answers }
+    }
+
+    /// The first example on the page, which is almost always the puzzle's example input.
+    pub fn example(&self) -> Option<&str> {
+        self.examples.first().map(String::as_str)
+    }
+
+    pub fn answer(&self, part: u8) -> Option<&str> {
+        self.answers
+            .get(usize::from(part).checked_sub(1)?)?
+            .as_deref()
+    }
+}
+
+/// Every substring of `s` between a `start` and the next `end`.
+fn between<'a>(s: &'a str, start: &'a str, end: &'a str) -> impl Iterator<Item = &'a str> {
+    s.split(start).skip(1).filter_map(move |rest| {
+        let end = rest.find(end)?;
+        Some(&rest[..end])
+    })
+}
+
+/// The answer to the example is the last emphasized code in a part's description,
+/// e.g. "... the total is <code><em>142</em></code>."
+fn expected_answer(article: &str) -> Option<String> {
+    let article = article
+        .replace("<em><code>", "<code><em>")
+        .replace("</code></em>", "</em></code>");
+    between(&article, "<code><em>", "</em></code>")
+        .last()
+        .map(text)
+        .filter(|answer| !answer.is_empty())
+}
+
+/// Strips tags from an HTML fragment and decodes the entities AoC uses.
+fn text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+impl HttpSource {
+    /// Fetches the puzzle description page. The session is optional here: without
+    /// one only part 1 is visible.
+    pub fn fetch_puzzle(&self, year: u16, day: u8) -> Result<String> {
+        let url = self.url(&format!("/{year}/day/{day}"));
+
+        let mut request = self.client()?.get(&url);
+        if let Some(session) = &self.session {
+            request = request.header("Cookie", format!("session={session}"));
+        }
+
+        let response = request
+            .send()
+            .context("Failed to send request")?
+            .error_for_status()
+            .context("AoC server returned an error")?;
+
+        response.text().context("Failed reading AoC response")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::tests::stub_server;
+    use chrono::Utc;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Test ---</h2>
+<p>For example:</p>
+<pre><code>1abc2
+pqr3stu8vwx
+a&lt;b&gt;c
+</code></pre>
+<p>In this example, the calibration values are <code>12</code> and <code>38</code>.
+Adding these together produces <code><em>142</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>54304</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<pre><code>two1<em>nine</em>
+</code></pre>
+<p>Adding these together produces <em><code>281</code></em>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn test_parse() {
+        let puzzle = Puzzle::parse(PAGE);
+        assert_eq!(
+            puzzle.examples,
+            vec!["1abc2\npqr3stu8vwx\na<b>c\n", "two1nine\n"]
+        );
+        assert_eq!(puzzle.example(), Some("1abc2\npqr3stu8vwx\na<b>c\n"));
+        assert_eq!(puzzle.answer(1), Some("142"));
+        assert_eq!(puzzle.answer(2), Some("281"));
+        assert_eq!(puzzle.answer(0), None);
+        assert_eq!(puzzle.answer(3), None);
+    }
+
+    #[test]
+    fn test_parse_part1_only() {
+        let puzzle = Puzzle::parse("<article><p>No emphasis here</p></article>");
+        assert!(puzzle.examples.is_empty());
+        assert_eq!(puzzle.answer(1), None);
+    }
+
+    #[test]
+    fn test_unlock_time() {
+        let unlock = unlock_time(2025, 1);
+        assert_eq!(unlock.to_rfc3339(), "2025-12-01T00:00:00-05:00");
+        assert_eq!(
+            unlock.with_timezone(&Utc).to_rfc3339(),
+            "2025-12-01T05:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_today() {
+        let utc = |s| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        // Still November 30th in New York
+        assert_eq!(today(utc("2025-12-01T04:59:59Z")), None);
+        assert_eq!(today(utc("2025-12-01T05:00:00Z")), Some((2025, 1)));
+        assert_eq!(today(utc("2025-12-12T12:00:00Z")), Some((2025, 12)));
+        assert_eq!(today(utc("2025-12-13T12:00:00Z")), None);
+        assert_eq!(today(utc("2024-12-25T12:00:00Z")), Some((2024, 25)));
+    }
+
+    #[test]
+    fn test_fetch_puzzle() {
+        let (url, requests) = stub_server("200 OK", PAGE);
+        let source = HttpSource::new("abc").with_base_url(url);
+
+        let puzzle = Puzzle::parse(&source.fetch_puzzle(2025, 1).unwrap());
+        assert_eq!(puzzle.answer(1), Some("142"));
+
+        let request = requests.recv().unwrap().to_lowercase();
+        assert!(request.starts_with("get /2025/day/1 http/1.1"));
+        assert!(request.contains("cookie: session=abc"));
+    }
+}
@@ -0,0 +1,537 @@
+use anyhow::{Context, Result, bail};
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::input::HttpSource;
+
+/// What the AoC website said about a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitResult {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited(Duration),
+    AlreadySolved,
+}
+
+impl fmt::Display for SubmitResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitResult::Correct => write!(f, "correct"),
+            SubmitResult::TooHigh => write!(f, "too_high"),
+            SubmitResult::TooLow => write!(f, "too_low"),
+            SubmitResult::Wrong => write!(f, "wrong"),
+            SubmitResult::RateLimited(wait) => write!(f, "rate_limited {}s", wait.as_secs()),
+            SubmitResult::AlreadySolved => write!(f, "already_solved"),
+        }
+    }
+}
+
+impl FromStr for SubmitResult {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "correct" => SubmitResult::Correct,
+            "too_high" => SubmitResult::TooHigh,
+            "too_low" => SubmitResult::TooLow,
+            "wrong" => SubmitResult::Wrong,
+            "already_solved" => SubmitResult::AlreadySolved,
+            _ => bail!("unknown submission result: {s}"),
+        })
+    }
+}
+
+/// Parses a wait like "1m 23s" out of "You have 1m 23s left to wait."
+fn parse_wait(text: &str) -> Option<Duration> {
+    let end = text.find(" left to wait")?;
+    let start = text[..end].rfind("you have ")? + "you have ".len();
+
+    let mut secs = 0;
+    for token in text[start..end].split_whitespace() {
+        let (num, unit) = token.split_at(token.char_indices().last()?.0);
+        let num: u64 = num.parse().ok()?;
+        secs += match unit {
+            "h" => num * 3600,
+            "m" => num * 60,
+            "s" => num,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(secs))
+}
+
+/// Turns the HTML page returned after posting an answer into a [`SubmitResult`].
+pub fn parse_response(html: &str) -> Result<SubmitResult> {
+    let text = html.to_lowercase();
+
+    if text.contains("that's the right answer") {
+        Ok(SubmitResult::Correct)
+    } else if text.contains("your answer is too high") {
+        Ok(SubmitResult::TooHigh)
+    } else if text.contains("your answer is too low") {
+        Ok(SubmitResult::TooLow)
+    } else if text.contains("that's not the right answer") {
+        Ok(SubmitResult::Wrong)
+    } else if text.contains("you gave an answer too recently") {
+        let wait = parse_wait(&text).unwrap_or(Duration::from_secs(60));
+        Ok(SubmitResult::RateLimited(wait))
+    } else if text.contains("you don't seem to be solving the right level") {
+        Ok(SubmitResult::AlreadySolved)
+    } else {
+        bail!("Could not make sense of the AoC response")
+    }
+}
+
+impl HttpSource {
+    /// Posts `answer` for the given part and parses the response page.
+    pub fn submit(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<SubmitResult> {
+        let session = self.session()?;
+        let url = self.url(&format!("/{year}/day/{day}/answer"));
+
+        let response = self
+            .client()?
+            .post(&url)
+            .header("Cookie", format!("session={session}"))
+            .form(&[("level", part.to_string().as_str()), ("answer", answer)])
+            .send()
+            .context("Failed to send request")?
+            .error_for_status()
+            .context("AoC server returned an error")?;
+
+        parse_response(&response.text().context("Failed reading AoC response")?)
+    }
+}
+
+/// Why an answer wasn't sent to the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    AlreadyCorrect,
+    AlreadySolved { answer: String },
+    AlreadyRejected { result: SubmitResult },
+    TooHigh { bound: i128 },
+    TooLow { bound: i128 },
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::AlreadyCorrect => write!(f, "this answer was already accepted"),
+            Refusal::AlreadySolved { answer } => {
+                write!(f, "this part was already solved with {answer}")
+            }
+            Refusal::AlreadyRejected { result } => {
+                write!(f, "this answer was already rejected ({result})")
+            }
+            Refusal::TooHigh { bound } => {
+                write!(f, "{bound} was too high, so the answer must be lower")
+            }
+            Refusal::TooLow { bound } => {
+                write!(f, "{bound} was too low, so the answer must be higher")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Refusal {}
+
+/// One submission in the answer log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub part: u8,
+    pub result: SubmitResult,
+    pub answer: String,
+}
+
+/// Every answer submitted for a day, stored next to the input cache as
+/// `{year}-{day}-answers.txt` with one `part result answer` line per submission.
+pub struct AnswerLog {
+    pub path: PathBuf,
+    pub submissions: Vec<Submission>,
+}
+
+impl AnswerLog {
+    pub fn path(cache_dir: &Path, year: u16, day: u8) -> PathBuf {
+        cache_dir.join(format!("{}-{:02}-answers.txt", year, day))
+    }
+
+    pub fn load(cache_dir: &Path, year: u16, day: u8) -> Result<Self> {
+        let path = Self::path(cache_dir, year, day);
+        // A log that can't be read must not pass for an empty one, or every answer
+        // would get through
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+
+        let submissions = contents
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let mut fields = line.splitn(3, ' ');
+                let (Some(part), Some(result), Some(answer)) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    bail!("malformed line in {}: {line}", path.display());
+                };
+                Ok(Submission {
+                    part: part.parse()?,
+                    result: result.parse()?,
+                    answer: answer.to_string(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { path, submissions })
+    }
+
+    /// Appends a submission to the log. Rate limited attempts, and those for a level
+    /// that isn't open (part 2 before part 1 is solved looks the same as a part that's
+    /// already done), weren't judged by the server, so they aren't recorded.
+    pub fn record(&mut self, submission: Submission) -> Result<()> {
+        if let SubmitResult::RateLimited(_) | SubmitResult::AlreadySolved = submission.result {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(
+            file,
+            "{} {} {}",
+            submission.part, submission.result, submission.answer
+        )?;
+        self.submissions.push(submission);
+        Ok(())
+    }
+
+    fn for_part(&self, part: u8) -> impl Iterator<Item = &Submission> {
+        self.submissions.iter().filter(move |s| s.part == part)
+    }
+
+    /// The lowest answer that was too high and the highest that was too low, if any.
+    pub fn bounds(&self, part: u8) -> (Option<i128>, Option<i128>) {
+        let numeric = |result| {
+            self.for_part(part)
+                .filter(move |s| s.result == result)
+                .filter_map(|s| s.answer.parse::<i128>().ok())
+        };
+        (
+            numeric(SubmitResult::TooLow).max(),
+            numeric(SubmitResult::TooHigh).min(),
+        )
+    }
+
+    /// Checks an answer against everything the server already told us, so that
+    /// known-wrong answers don't cost another rate limit timeout.
+    pub fn check(&self, part: u8, answer: &str) -> Result<(), Refusal> {
+        if let Some(correct) = self.correct_answer(part) {
+            return Err(if correct == answer {
+                Refusal::AlreadyCorrect
+            } else {
+                Refusal::AlreadySolved {
+                    answer: correct.to_string(),
+                }
+            });
+        }
+
+        let rejected = |s: &&Submission| {
+            matches!(
+                s.result,
+                SubmitResult::TooHigh | SubmitResult::TooLow | SubmitResult::Wrong
+            )
+        };
+        if let Some(previous) = self
+            .for_part(part)
+            .filter(rejected)
+            .find(|s| s.answer == answer)
+        {
+            return Err(Refusal::AlreadyRejected {
+                result: previous.result,
+            });
+        }
+
+        if let Ok(value) = answer.parse::<i128>() {
+            let (low, high) = self.bounds(part);
+            if let Some(bound) = high.filter(|&high| value >= high) {
+                return Err(Refusal::TooHigh { bound });
+            }
+            if let Some(bound) = low.filter(|&low| value <= low) {
+                return Err(Refusal::TooLow { bound });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn correct_answer(&self, part: u8) -> Option<&str> {
+        self.submissions
+            .iter()
+            .find(|s| s.part == part && s.result == SubmitResult::Correct)
+            .map(|s| s.answer.as_str())
+    }
+}
+
+/// Submits an answer and records the outcome in the answer log. Answers that the log
+/// shows can't be right are refused with a [`Refusal`] error without contacting the server.
+pub fn submit_with(
+    source: &HttpSource,
+    cache_dir: &Path,
+    year: u16,
+    day: u8,
+    part: u8,
+    answer: &str,
+) -> Result<SubmitResult> {
+    let mut log = AnswerLog::load(cache_dir, year, day)?;
+    log.check(part, answer)?;
+    let result = source.submit(year, day, part, answer)?;
+    log.record(Submission {
+        part,
+        result,
+        answer: answer.to_string(),
+    })?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::tests::{stub_server, temp_dir};
+
+    fn page(message: &str) -> String {
+        format!("<html><body><main><article><p>{message}</p></article></main></body></html>")
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let cases = [
+            (
+                "That's the right answer!  You are one gold star closer.",
+                SubmitResult::Correct,
+            ),
+            (
+                "That's not the right answer; your answer is too high.  Please wait one minute.",
+                SubmitResult::TooHigh,
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                SubmitResult::TooLow,
+            ),
+            (
+                "That's not the right answer.  If you're stuck, make sure you're using the full input data.",
+                SubmitResult::Wrong,
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 23s left to wait.",
+                SubmitResult::RateLimited(Duration::from_secs(83)),
+            ),
+            (
+                "You gave an answer too recently. You have 9s left to wait.",
+                SubmitResult::RateLimited(Duration::from_secs(9)),
+            ),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it?",
+                SubmitResult::AlreadySolved,
+            ),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(parse_response(&page(message)).unwrap(), expected);
+        }
+        assert!(parse_response(&page("Something else")).is_err());
+    }
+
+    #[test]
+    fn test_parse_wait() {
+        assert_eq!(
+            parse_wait("you have 1h 2m 3s left to wait."),
+            Some(Duration::from_secs(3723))
+        );
+        // Anything odd in the page falls back to the default wait instead of panicking
+        assert_eq!(parse_wait("you have 5é left to wait."), None);
+        assert_eq!(parse_wait("you have 5 left to wait."), None);
+        assert_eq!(parse_wait("you have é left to wait."), None);
+    }
+
+    #[test]
+    fn test_result_roundtrip() {
+        for result in [
+            SubmitResult::Correct,
+            SubmitResult::TooHigh,
+            SubmitResult::TooLow,
+            SubmitResult::Wrong,
+            SubmitResult::AlreadySolved,
+        ] {
+            assert_eq!(result.to_string().parse::<SubmitResult>().unwrap(), result);
+        }
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, requests) = stub_server("200 OK", &page("That's the right answer!"));
+        let source = HttpSource::new("abc").with_base_url(url);
+        let cache = temp_dir("submit");
+        // As scaffolded by new_day
+        fs::write(AnswerLog::path(&cache, 2025, 4), "# part result answer\n").unwrap();
+
+        let result = submit_with(&source, &cache, 2025, 4, 2, "8948").unwrap();
+        assert_eq!(result, SubmitResult::Correct);
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /2025/day/4/answer HTTP/1.1"));
+        assert!(request.ends_with("level=2&answer=8948"));
+
+        let log = AnswerLog::load(&cache, 2025, 4).unwrap();
+        assert_eq!(log.correct_answer(2), Some("8948"));
+        assert_eq!(log.correct_answer(1), None);
+    }
+
+    fn log_with(submissions: &[(u8, SubmitResult, &str)]) -> AnswerLog {
+        AnswerLog {
+            path: PathBuf::new(),
+            submissions: submissions
+                .iter()
+                .map(|&(part, result, answer)| Submission {
+                    part,
+                    result,
+                    answer: answer.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let log = log_with(&[
+            (1, SubmitResult::TooHigh, "5000"),
+            (1, SubmitResult::TooHigh, "4000"),
+            (1, SubmitResult::TooLow, "1000"),
+            (1, SubmitResult::Wrong, "2500"),
+            (2, SubmitResult::Correct, "abc"),
+        ]);
+
+        assert_eq!(log.bounds(1), (Some(1000), Some(4000)));
+        assert_eq!(log.check(1, "3000"), Ok(()));
+        assert_eq!(log.check(1, "4001"), Err(Refusal::TooHigh { bound: 4000 }));
+        assert_eq!(log.check(1, "4500"), Err(Refusal::TooHigh { bound: 4000 }));
+        assert_eq!(log.check(1, "999"), Err(Refusal::TooLow { bound: 1000 }));
+        assert_eq!(
+            log.check(1, "2500"),
+            Err(Refusal::AlreadyRejected {
+                result: SubmitResult::Wrong
+            })
+        );
+        assert_eq!(log.check(1, "not a number"), Ok(()));
+
+        assert_eq!(log.check(2, "abc"), Err(Refusal::AlreadyCorrect));
+        assert_eq!(
+            log.check(2, "xyz"),
+            Err(Refusal::AlreadySolved {
+                answer: "abc".to_string()
+            })
+        );
+        assert_eq!(log.bounds(2), (None, None));
+    }
+
+    #[test]
+    fn test_submit_refuses_known_wrong_answers() {
+        let cache = temp_dir("refuse");
+        let mut log = AnswerLog::load(&cache, 2025, 3).unwrap();
+        log.record(Submission {
+            part: 1,
+            result: SubmitResult::TooHigh,
+            answer: "100".to_string(),
+        })
+        .unwrap();
+
+        // Nothing is listening here, so this would fail if it got as far as the request
+        let source = HttpSource::new("abc").with_base_url("http://127.0.0.1:1");
+        let err = submit_with(&source, &cache, 2025, 3, 1, "150").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Refusal>(),
+            Some(&Refusal::TooHigh { bound: 100 })
+        );
+        assert_eq!(
+            err.to_string(),
+            "100 was too high, so the answer must be lower"
+        );
+    }
+
+    #[test]
+    fn test_unjudged_answers_can_be_resubmitted() {
+        let cache = temp_dir("unjudged");
+        // Part 2 sent before part 1 was solved, from a log written before these were
+        // left out
+        fs::write(AnswerLog::path(&cache, 2025, 5), "1 already_solved 7\n").unwrap();
+
+        let level =
+            page("You don't seem to be solving the right level.  Did you already complete it?");
+        let (url, _requests) = stub_server("200 OK", &level);
+        let source = HttpSource::new("abc").with_base_url(url);
+        let result = submit_with(&source, &cache, 2025, 5, 2, "42").unwrap();
+        assert_eq!(result, SubmitResult::AlreadySolved);
+
+        let log = AnswerLog::load(&cache, 2025, 5).unwrap();
+        assert_eq!(log.submissions.len(), 1);
+        assert_eq!(log.check(1, "7"), Ok(()));
+        assert_eq!(log.check(2, "42"), Ok(()));
+
+        let (url, _requests) = stub_server("200 OK", &page("That's the right answer!"));
+        let source = HttpSource::new("abc").with_base_url(url);
+        let result = submit_with(&source, &cache, 2025, 5, 2, "42").unwrap();
+        assert_eq!(result, SubmitResult::Correct);
+    }
+
+    #[test]
+    fn test_unreadable_log() {
+        let cache = temp_dir("unreadable-log");
+        assert!(
+            AnswerLog::load(&cache, 2025, 6)
+                .unwrap()
+                .submissions
+                .is_empty()
+        );
+
+        fs::create_dir(AnswerLog::path(&cache, 2025, 6)).unwrap();
+        assert!(AnswerLog::load(&cache, 2025, 6).is_err());
+
+        fs::write(AnswerLog::path(&cache, 2025, 7), b"1 wrong \xff\n").unwrap();
+        assert!(AnswerLog::load(&cache, 2025, 7).is_err());
+    }
+
+    #[test]
+    fn test_rate_limited_is_not_logged() {
+        let cache = temp_dir("answer-log");
+        let mut log = AnswerLog::load(&cache, 2025, 1).unwrap();
+        log.record(Submission {
+            part: 1,
+            result: SubmitResult::RateLimited(Duration::from_secs(30)),
+            answer: "1".to_string(),
+        })
+        .unwrap();
+        log.record(Submission {
+            part: 1,
+            result: SubmitResult::TooLow,
+            answer: "2".to_string(),
+        })
+        .unwrap();
+
+        let log = AnswerLog::load(&cache, 2025, 1).unwrap();
+        assert_eq!(
+            log.submissions,
+            vec![Submission {
+                part: 1,
+                result: SubmitResult::TooLow,
+                answer: "2".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Sets of small integers packed into bits, for visited sets and occupancy maps that
+//! get copied, compared and hashed a lot.
+
+/// A set of `usize`s, one bit each. It grows to fit whatever is inserted, and two sets
+/// with the same members are equal however they got there.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BitSet {
+    // Never ends in a zero word, so equal sets have equal words
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `i`, returning whether it wasn't already there.
+    pub fn insert(&mut self, i: usize) -> bool {
+        let (word, bit) = (i / 64, 1 << (i % 64));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let added = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        added
+    }
+
+    /// Removes `i`, returning whether it was there.
+    pub fn remove(&mut self, i: usize) -> bool {
+        if !self.contains(i) {
+            return false;
+        }
+        self.words[i / 64] &= !(1 << (i % 64));
+        self.trim();
+        true
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        self.words
+            .get(i / 64)
+            .is_some_and(|word| word & (1 << (i % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// The members, smallest first.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| w * 64 + bit)
+        })
+    }
+
+    pub fn is_disjoint(&self, other: &BitSet) -> bool {
+        self.words.iter().zip(&other.words).all(|(a, b)| a & b == 0)
+    }
+
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        self.words.len() <= other.words.len()
+            && self
+                .words
+                .iter()
+                .zip(&other.words)
+                .all(|(a, b)| a & !b == 0)
+    }
+
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        let (long, short) = if self.words.len() >= other.words.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut union = long.clone();
+        for (word, other) in union.words.iter_mut().zip(&short.words) {
+            *word |= other;
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        let mut intersection = BitSet {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a & b)
+                .collect(),
+        };
+        intersection.trim();
+        intersection
+    }
+
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for i in iter {
+            self.insert(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitset() {
+        let mut set = BitSet::new();
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(130));
+        assert!(set.contains(130) && !set.contains(2) && !set.contains(1000));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 130]);
+
+        // Removing the high bit leaves the same set as never having added it
+        assert!(set.remove(130));
+        assert!(!set.remove(130));
+        assert_eq!(set, BitSet::from_iter([3]));
+
+        let evens: BitSet = (0..100).step_by(2).collect();
+        let odds: BitSet = (1..100).step_by(2).collect();
+        assert!(evens.is_disjoint(&odds));
+        assert!(set.is_subset(&odds) && !set.is_subset(&evens));
+        assert_eq!(evens.union(&odds), (0..100).collect());
+        assert!(evens.intersection(&odds).is_empty());
+        assert_eq!(
+            evens.intersection(&(0..5).collect()),
+            [0, 2, 4].into_iter().collect()
+        );
+    }
+}
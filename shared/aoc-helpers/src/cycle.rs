@@ -0,0 +1,155 @@
+//! Finding where a simulation starts repeating itself, so it can be skipped ahead
+//! to a step that would take far too long to reach.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A sequence of states that repeats forever once step `start` is reached: the
+/// state after `start + len` steps is the same as after `start`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// The earliest step whose state is the same as the state at `step`.
+    pub fn equivalent_step(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.len
+        }
+    }
+}
+
+/// Runs `next` from `state` until two states have the same `key`, remembering every
+/// key seen. The key only needs to capture what decides the states that follow, e.g.
+/// the top few rows of a tower rather than all of it.
+pub fn find_cycle<S, K: Hash + Eq>(
+    state: S,
+    next: impl FnMut(S) -> S,
+    key: impl FnMut(&S) -> K,
+) -> Cycle {
+    run_until_cycle(state, next, key, |_| ()).0
+}
+
+/// Brent's algorithm, for when the states themselves can be compared: it only ever
+/// keeps two of them, however long the run is before it repeats.
+pub fn find_cycle_brent<S: Clone + Eq>(state: S, mut next: impl FnMut(&S) -> S) -> Cycle {
+    // Find the cycle length by looking for the tortoise with a hare that keeps
+    // doubling how far ahead it is allowed to get
+    let (mut power, mut len) = (1, 1);
+    let mut tortoise = state.clone();
+    let mut hare = next(&state);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = next(&hare);
+        len += 1;
+    }
+
+    // Then walk two states `len` apart until they meet at the start of the cycle
+    let mut tortoise = state.clone();
+    let mut hare = state;
+    for _ in 0..len {
+        hare = next(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        start += 1;
+    }
+    Cycle { start, len }
+}
+
+/// What `value` would be after `n` steps of `next`, assuming it goes up by the same
+/// amount every time around the cycle found by [`find_cycle`].
+pub fn extrapolate<S, K: Hash + Eq>(
+    state: S,
+    next: impl FnMut(S) -> S,
+    key: impl FnMut(&S) -> K,
+    value: impl FnMut(&S) -> i64,
+    n: usize,
+) -> i64 {
+    let (cycle, values) = run_until_cycle(state, next, key, value);
+    if n < values.len() {
+        return values[n];
+    }
+    let per_cycle = values[cycle.start + cycle.len] - values[cycle.start];
+    let cycles = ((n - cycle.start) / cycle.len) as i64;
+    values[cycle.equivalent_step(n)] + cycles * per_cycle
+}
+
+/// Runs until a key repeats, returning the cycle and `value` for every state up to
+/// and including the first repeat.
+fn run_until_cycle<S, K: Hash + Eq, V>(
+    mut state: S,
+    mut next: impl FnMut(S) -> S,
+    mut key: impl FnMut(&S) -> K,
+    mut value: impl FnMut(&S) -> V,
+) -> (Cycle, Vec<V>) {
+    let mut seen = HashMap::new();
+    let mut values = vec![];
+    for step in 0.. {
+        values.push(value(&state));
+        if let Some(start) = seen.insert(key(&state), step) {
+            let cycle = Cycle {
+                start,
+                len: step - start,
+            };
+            return (cycle, values);
+        }
+        state = next(state);
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn next(x: &u64) -> u64 {
+        (x * x + 1) % 11
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        // x² + 1 mod 11 from 0 goes 0, 1, 2, 5, 4, 6, 4, ...
+        let cycle = find_cycle(0, |x| next(&x), |&x| x);
+        assert_eq!(cycle, Cycle { start: 4, len: 2 });
+        assert_eq!(find_cycle_brent(0, next), cycle);
+
+        assert_eq!(cycle.equivalent_step(3), 3);
+        assert_eq!(cycle.equivalent_step(4), 4);
+        assert_eq!(cycle.equivalent_step(7), 5);
+        assert_eq!(cycle.equivalent_step(1_000_000), 4);
+
+        // Already in the cycle from the start
+        assert_eq!(
+            find_cycle_brent(7u64, |x| (x + 3) % 9),
+            Cycle { start: 0, len: 3 }
+        );
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // A counter that wraps around every 3 steps after a 2 step run up, and a total
+        // that goes up by the counter each step
+        let step = |(i, total): (u64, i64)| {
+            let i = if i < 4 { i + 1 } else { 2 };
+            (i, total + i as i64)
+        };
+        let brute_force = |n| (0..n).fold((0, 0), |state, _| step(state)).1;
+        for n in [0, 1, 4, 5, 6, 7, 100, 1001] {
+            assert_eq!(
+                extrapolate((0, 0), step, |&(i, _)| i, |&(_, total)| total, n),
+                brute_force(n),
+                "after {n} steps"
+            );
+        }
+    }
+}
@@ -0,0 +1,371 @@
+//! Named expressions that refer to each other, like monkeys shouting out numbers:
+//! evaluating them exactly, and solving for one name that isn't known.
+use std::collections::HashMap;
+use std::fmt;
+
+use num::rational::Ratio;
+use num::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Zero};
+use parse_display::{Display, FromStr, ParseError};
+
+use super::graph::Interner;
+
+pub type Rational = Ratio<i128>;
+
+#[derive(Debug, Display, FromStr, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    #[display("+")]
+    Add,
+    #[display("-")]
+    Sub,
+    #[display("*")]
+    Mul,
+    #[display("/")]
+    Div,
+}
+
+impl Op {
+    pub fn apply(self, a: Rational, b: Rational) -> Result<Rational, ExprError> {
+        match self {
+            Op::Add => a.checked_add(&b),
+            Op::Sub => a.checked_sub(&b),
+            Op::Mul => a.checked_mul(&b),
+            Op::Div if b.is_zero() => return Err(ExprError::DivisionByZero),
+            Op::Div => a.checked_div(&b),
+        }
+        .ok_or(ExprError::Overflow)
+    }
+}
+
+/// The right hand side of an assignment, referring to other names by index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node {
+    Num(Rational),
+    Op(Op, usize, usize),
+}
+
+/// An expression with everything that doesn't depend on the unknown folded into a
+/// single number, so anything that isn't a `Num` involves the unknown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Num(Rational),
+    Var(String),
+    Op(Op, Box<Expr>, Box<Expr>),
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{n}"),
+            Expr::Var(name) => write!(f, "{name}"),
+            Expr::Op(op, a, b) => write!(f, "({a} {op} {b})"),
+        }
+    }
+}
+
+/// Why an expression couldn't be evaluated or solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprError {
+    /// A name that is used but never assigned.
+    Undefined(String),
+    /// A name whose value depends on itself.
+    Cycle(String),
+    DivisionByZero,
+    /// The equation doesn't involve the unknown at all.
+    NoUnknown(String),
+    /// The unknown appears in both operands of some operation, so it can't be
+    /// isolated by undoing one operation at a time.
+    UnknownOnBothSides,
+    /// The equation holds for no value, or for every value, of the unknown.
+    NoUniqueSolution,
+    /// A numerator or denominator got too big for an `i128`.
+    Overflow,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprError::Undefined(name) => write!(f, "{name} is never assigned"),
+            ExprError::Cycle(name) => write!(f, "{name} depends on itself"),
+            ExprError::DivisionByZero => write!(f, "division by zero"),
+            ExprError::NoUnknown(name) => write!(f, "{name} doesn't appear in the equation"),
+            ExprError::UnknownOnBothSides => {
+                write!(f, "the unknown appears on both sides of an operation")
+            }
+            ExprError::NoUniqueSolution => write!(f, "the equation has no unique solution"),
+            ExprError::Overflow => write!(f, "a number got too big to work out exactly"),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// A list of assignments like `root: pppw + sjmn` or `dvpt: 3`, one per line. Names
+/// can be used before they're assigned, and any number of times, so the expressions
+/// form a DAG rather than a tree.
+#[derive(Debug, Clone)]
+pub struct Dag {
+    names: Interner<String>,
+    nodes: Vec<Option<Node>>,
+}
+
+impl std::str::FromStr for Dag {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut names = Interner::new();
+        let mut assignments = vec![];
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, rhs) = line
+                .split_once(':')
+                .ok_or_else(|| ParseError::with_message("expected name: value"))?;
+            let id = names.intern(name.trim());
+            let node = match rhs.split_whitespace().collect::<Vec<&str>>()[..] {
+                [n] => {
+                    let n: i128 = n
+                        .parse()
+                        .map_err(|_| ParseError::with_message("invalid number"))?;
+                    Node::Num(n.into())
+                }
+                [a, op, b] => Node::Op(op.parse()?, names.intern(a), names.intern(b)),
+                _ => {
+                    return Err(ParseError::with_message(
+                        "expected a number or an operation",
+                    ))
+                }
+            };
+            assignments.push((id, node));
+        }
+        let mut nodes = vec![None; names.len()];
+        for (id, node) in assignments {
+            nodes[id] = Some(node);
+        }
+        Ok(Dag { names, nodes })
+    }
+}
+
+impl Dag {
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.names.get(name)
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        self.names.name(id)
+    }
+
+    /// What `name` is assigned, or `None` if it isn't.
+    pub fn node(&self, name: &str) -> Option<Node> {
+        self.nodes[self.id(name)?]
+    }
+
+    /// The exact value of `name`.
+    pub fn eval(&self, name: &str) -> Result<Rational, ExprError> {
+        match self.simplify(name, None)? {
+            Expr::Num(n) => Ok(n),
+            _ => unreachable!("nothing is unknown"),
+        }
+    }
+
+    /// `name` written out in terms of `unknown`, with every part that doesn't depend
+    /// on it worked out.
+    pub fn simplify(&self, name: &str, unknown: Option<&str>) -> Result<Expr, ExprError> {
+        let id = self
+            .id(name)
+            .ok_or_else(|| ExprError::Undefined(name.to_string()))?;
+        let unknown = unknown.and_then(|unknown| self.id(unknown));
+        self.fold(id, unknown, &mut HashMap::new())
+    }
+
+    fn fold(
+        &self,
+        id: usize,
+        unknown: Option<usize>,
+        // `None` while a name is still being folded, to spot cycles
+        folded: &mut HashMap<usize, Option<Expr>>,
+    ) -> Result<Expr, ExprError> {
+        if Some(id) == unknown {
+            return Ok(Expr::Var(self.name(id).to_string()));
+        }
+        match folded.get(&id) {
+            Some(Some(expr)) => return Ok(expr.clone()),
+            Some(None) => return Err(ExprError::Cycle(self.name(id).to_string())),
+            None => {}
+        }
+        folded.insert(id, None);
+        let expr = match self.nodes[id] {
+            None => return Err(ExprError::Undefined(self.name(id).to_string())),
+            Some(Node::Num(n)) => Expr::Num(n),
+            Some(Node::Op(op, a, b)) => {
+                match (
+                    self.fold(a, unknown, folded)?,
+                    self.fold(b, unknown, folded)?,
+                ) {
+                    (Expr::Num(a), Expr::Num(b)) => Expr::Num(op.apply(a, b)?),
+                    (a, b) => Expr::Op(op, Box::new(a), Box::new(b)),
+                }
+            }
+        };
+        folded.insert(id, Some(expr.clone()));
+        Ok(expr)
+    }
+
+    /// The value of `unknown` that makes `lhs` and `rhs` equal, ignoring whatever
+    /// `unknown` is assigned.
+    pub fn solve(&self, lhs: &str, rhs: &str, unknown: &str) -> Result<Rational, ExprError> {
+        let lhs = self.simplify(lhs, Some(unknown))?;
+        let rhs = self.simplify(rhs, Some(unknown))?;
+        let (mut expr, mut target) = match (lhs, rhs) {
+            (Expr::Num(_), Expr::Num(_)) => return Err(ExprError::NoUnknown(unknown.to_string())),
+            (expr, Expr::Num(n)) | (Expr::Num(n), expr) => (expr, n),
+            _ => return Err(ExprError::UnknownOnBothSides),
+        };
+
+        // Undo the outermost operation on both sides until only the unknown is left
+        loop {
+            let (op, a, b) = match expr {
+                Expr::Num(_) => unreachable!("the unknown is always on this side"),
+                Expr::Var(_) => return Ok(target),
+                Expr::Op(op, a, b) => (op, *a, *b),
+            };
+            (expr, target) = match (op, a, b) {
+                (_, Expr::Num(_), Expr::Num(_)) => unreachable!("constants are folded"),
+                (Op::Add, e, Expr::Num(n)) | (Op::Add, Expr::Num(n), e) => {
+                    (e, Op::Sub.apply(target, n)?)
+                }
+                (Op::Sub, e, Expr::Num(n)) => (e, Op::Add.apply(target, n)?),
+                (Op::Sub, Expr::Num(n), e) => (e, Op::Sub.apply(n, target)?),
+                (Op::Mul, e, Expr::Num(n)) | (Op::Mul, Expr::Num(n), e) => {
+                    if n.is_zero() {
+                        return Err(ExprError::NoUniqueSolution);
+                    }
+                    (e, Op::Div.apply(target, n)?)
+                }
+                (Op::Div, e, Expr::Num(n)) => {
+                    if n.is_zero() {
+                        return Err(ExprError::DivisionByZero);
+                    }
+                    (e, Op::Mul.apply(target, n)?)
+                }
+                (Op::Div, Expr::Num(n), e) => {
+                    if target.is_zero() {
+                        return Err(ExprError::NoUniqueSolution);
+                    }
+                    (e, Op::Div.apply(n, target)?)
+                }
+                _ => return Err(ExprError::UnknownOnBothSides),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONKEYS: &str = "root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32";
+
+    fn parse(input: &str) -> Dag {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn test_eval() {
+        let monkeys = parse(MONKEYS);
+        assert_eq!(monkeys.eval("root"), Ok(152.into()));
+        assert_eq!(
+            monkeys.simplify("pppw", Some("humn")).unwrap().to_string(),
+            "((4 + (2 * (humn - 3))) / 4)"
+        );
+
+        // Division is exact rather than rounding down
+        assert_eq!(
+            parse("a: b / c\nb: 7\nc: 2").eval("a"),
+            Ok(Rational::new(7, 2))
+        );
+        assert_eq!(
+            parse("a: b / c\nb: 7\nc: 0").eval("a"),
+            Err(ExprError::DivisionByZero)
+        );
+        assert_eq!(
+            parse("a: b + c\nb: 7").eval("a"),
+            Err(ExprError::Undefined("c".to_string()))
+        );
+        assert_eq!(
+            parse("a: b + c\nb: 7\nc: a * b").eval("a"),
+            Err(ExprError::Cycle("a".to_string()))
+        );
+        assert!("a: b % c".parse::<Dag>().is_err());
+    }
+
+    #[test]
+    fn test_solve() {
+        let monkeys = parse(MONKEYS);
+        assert_eq!(monkeys.solve("pppw", "sjmn", "humn"), Ok(301.into()));
+
+        // 10 / (x - 1) = 4
+        let monkeys = parse("a: b / c\nb: 10\nc: x - d\nd: 1\ne: 4");
+        assert_eq!(monkeys.solve("a", "e", "x"), Ok(Rational::new(7, 2)));
+        assert_eq!(
+            monkeys.solve("b", "e", "x"),
+            Err(ExprError::NoUnknown("x".to_string()))
+        );
+
+        // x * x = 4, and x - 1 = x + 2
+        let monkeys = parse("a: x * x\nb: 4\nc: x - d\nd: 1\ne: x + f\nf: 2");
+        assert_eq!(
+            monkeys.solve("a", "b", "x"),
+            Err(ExprError::UnknownOnBothSides)
+        );
+        assert_eq!(
+            monkeys.solve("c", "e", "x"),
+            Err(ExprError::UnknownOnBothSides)
+        );
+
+        // 0 * x = 4
+        let monkeys = parse("a: x * b\nb: 0\nc: 4");
+        assert_eq!(
+            monkeys.solve("a", "c", "x"),
+            Err(ExprError::NoUniqueSolution)
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let max = i128::MAX;
+        assert_eq!(
+            parse(&format!("a: b + b\nb: {max}")).eval("a"),
+            Err(ExprError::Overflow)
+        );
+        assert_eq!(
+            parse(&format!("a: b * b\nb: {max}")).eval("a"),
+            Err(ExprError::Overflow)
+        );
+        // Adding fractions cross-multiplies the denominators
+        assert_eq!(
+            parse(&format!(
+                "a: b + d\nb: c / m\nc: 1\nm: {max}\nd: c / n\nn: {}",
+                max - 1
+            ))
+            .eval("a"),
+            Err(ExprError::Overflow)
+        );
+
+        // x - MAX = MAX, and x / MAX = 2
+        let monkeys = parse(&format!("a: x - b\nb: {max}\nc: x / b\nd: 2"));
+        assert_eq!(monkeys.solve("a", "b", "x"), Err(ExprError::Overflow));
+        assert_eq!(monkeys.solve("c", "d", "x"), Err(ExprError::Overflow));
+    }
+}
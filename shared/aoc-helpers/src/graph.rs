@@ -0,0 +1,196 @@
+//! Weighted graphs over dense node indices: naming nodes, shortest distances between
+//! every pair of them, and shrinking a graph down to the nodes that matter.
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Hands out indices `0, 1, 2, ...` to names in the order they're first seen, so
+/// nodes can be kept in `Vec`s and [`BitSet`](super::bitset::BitSet)s.
+#[derive(Debug, Clone)]
+pub struct Interner<K> {
+    ids: HashMap<K, usize>,
+    names: Vec<K>,
+}
+
+impl<K> Default for Interner<K> {
+    fn default() -> Self {
+        Interner {
+            ids: HashMap::new(),
+            names: vec![],
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> Interner<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The index for `name`, giving it the next one if it hasn't got one yet.
+    pub fn intern(&mut self, name: impl Into<K>) -> usize {
+        let name = name.into();
+        if let Some(&id) = self.ids.get(&name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.ids.insert(name.clone(), id);
+        self.names.push(name);
+        id
+    }
+
+    pub fn get<Q>(&self, name: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &K {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// A directed graph whose nodes are `0..len()`, each edge with a cost.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Graph {
+    edges: Vec<Vec<(usize, usize)>>,
+}
+
+impl Graph {
+    /// A graph of `len` nodes and no edges.
+    pub fn new(len: usize) -> Self {
+        Graph {
+            edges: vec![vec![]; len],
+        }
+    }
+
+    /// Adds an edge, adding nodes too if either end is past the last one.
+    pub fn add_edge(&mut self, from: usize, to: usize, cost: usize) {
+        let len = from.max(to) + 1;
+        if len > self.edges.len() {
+            self.edges.resize(len, vec![]);
+        }
+        self.edges[from].push((to, cost));
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// The nodes one edge away from `node`, with what each edge costs.
+    pub fn neighbors(&self, node: usize) -> &[(usize, usize)] {
+        &self.edges[node]
+    }
+
+    /// The shortest distance between every pair of nodes, using Floyd–Warshall.
+    pub fn distances(&self) -> Distances {
+        let len = self.len();
+        let mut dist = vec![None; len * len];
+        for from in 0..len {
+            dist[from * len + from] = Some(0);
+            for &(to, cost) in &self.edges[from] {
+                let d: &mut Option<usize> = &mut dist[from * len + to];
+                *d = Some(d.map_or(cost, |d| d.min(cost)));
+            }
+        }
+        for via in 0..len {
+            for from in 0..len {
+                let Some(first) = dist[from * len + via] else {
+                    continue;
+                };
+                for to in 0..len {
+                    let Some(second) = dist[via * len + to] else {
+                        continue;
+                    };
+                    let d = &mut dist[from * len + to];
+                    if d.is_none_or(|d| first + second < d) {
+                        *d = Some(first + second);
+                    }
+                }
+            }
+        }
+        Distances { len, dist }
+    }
+
+    /// A graph of only the `keep` nodes, where node `i` is `keep[i]` and there is an
+    /// edge wherever one kept node can reach another, costing the shortest route
+    /// between them in this graph.
+    pub fn contract(&self, keep: &[usize]) -> Graph {
+        let distances = self.distances();
+        let mut contracted = Graph::new(keep.len());
+        for (i, &from) in keep.iter().enumerate() {
+            for (j, &to) in keep.iter().enumerate() {
+                if let Some(cost) = distances.get(from, to).filter(|_| i != j) {
+                    contracted.add_edge(i, j, cost);
+                }
+            }
+        }
+        contracted
+    }
+}
+
+/// The shortest distances found by [`Graph::distances`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distances {
+    len: usize,
+    dist: Vec<Option<usize>>,
+}
+
+impl Distances {
+    /// The cost of the cheapest route from `from` to `to`, or `None` if there isn't
+    /// one.
+    pub fn get(&self, from: usize, to: usize) -> Option<usize> {
+        self.dist[from * self.len + to]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut names: Interner<String> = Interner::new();
+        assert_eq!(names.intern("AA"), 0);
+        assert_eq!(names.intern("BB"), 1);
+        assert_eq!(names.intern("AA"), 0);
+        assert_eq!(names.get("BB"), Some(1));
+        assert_eq!(names.get("CC"), None);
+        assert_eq!(names.name(1), "BB");
+        assert_eq!(names.len(), 2);
+    }
+
+    #[test]
+    fn test_distances_and_contract() {
+        // A line 0 - 1 - 2 - 3 with a slow shortcut from 0 to 3, and 4 on its own
+        let mut graph = Graph::new(5);
+        for (a, b, cost) in [(0, 1, 1), (1, 2, 1), (2, 3, 1), (0, 3, 5)] {
+            graph.add_edge(a, b, cost);
+            graph.add_edge(b, a, cost);
+        }
+        let distances = graph.distances();
+        assert_eq!(distances.get(0, 3), Some(3));
+        assert_eq!(distances.get(3, 1), Some(2));
+        assert_eq!(distances.get(2, 2), Some(0));
+        assert_eq!(distances.get(0, 4), None);
+
+        let contracted = graph.contract(&[0, 3, 4]);
+        assert_eq!(contracted.len(), 3);
+        assert_eq!(contracted.neighbors(0), &[(1, 3)]);
+        assert_eq!(contracted.neighbors(1), &[(0, 3)]);
+        assert!(contracted.neighbors(2).is_empty());
+    }
+}
@@ -0,0 +1,312 @@
+//! Exporting grids as PNG images and simulations as animated GIFs.
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::render::YAxis;
+use super::{Bounds, Coord, Grid};
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+/// Command line flag naming a file to save a simulation to as an animated GIF.
+pub const GIF_FLAG: &str = "--gif";
+
+/// The path given after `--gif`, e.g. `cargo solve 14 -- --gif sand.gif`.
+pub fn gif_path() -> Option<PathBuf> {
+    let mut args = std::env::args_os().skip_while(|arg| arg != GIF_FLAG);
+    args.next()?;
+    args.next().map(PathBuf::from)
+}
+
+/// An RGB image, three bytes per pixel, row by row from the top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, background: Rgb) -> Self {
+        Image {
+            width,
+            height,
+            pixels: background.repeat(width as usize * height as usize),
+        }
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        3 * (y as usize * self.width as usize + x as usize)
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Rgb {
+        let i = self.index(x, y);
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, colour: Rgb) {
+        let i = self.index(x, y);
+        self.pixels[i..i + 3].copy_from_slice(&colour);
+    }
+
+    pub fn write_png(&self, w: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(w, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(io::Error::other)
+    }
+
+    pub fn save_png(&self, path: &Path) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// The cells of a grid coloured in, one set per frame of a simulation. Frames are
+/// only turned into pixels when saving, so the image covers every frame even if the
+/// grid grows as the simulation runs.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<HashMap<Coord, Rgb>>,
+    pub viewport: Option<Bounds>,
+    pub y_axis: YAxis,
+    pub background: Rgb,
+    /// Pixels per cell along each side.
+    pub scale: u32,
+    pub delay: Duration,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Animation {
+            frames: vec![],
+            viewport: None,
+            y_axis: YAxis::Down,
+            background: BLACK,
+            scale: 4,
+            delay: Duration::from_millis(100),
+        }
+    }
+
+    pub fn with_viewport(mut self, bounds: Bounds) -> Self {
+        self.viewport = Some(bounds);
+        self
+    }
+
+    pub fn with_y_axis(mut self, y_axis: YAxis) -> Self {
+        self.y_axis = y_axis;
+        self
+    }
+
+    pub fn with_background(mut self, background: Rgb) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: u32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Starts a new frame with every cell of `grid` that `colour` gives a colour to.
+    pub fn capture<T>(&mut self, grid: &impl Grid<T>, colour: impl Fn(&T) -> Option<Rgb>) {
+        let frame = grid
+            .iter()
+            .filter_map(|(pos, value)| Some((pos, colour(value)?)))
+            .collect();
+        self.frames.push(frame);
+    }
+
+    /// Draws `cells` over the latest frame, e.g. the piece that is still moving.
+    pub fn overlay(&mut self, cells: impl IntoIterator<Item = (Coord, Rgb)>) {
+        if self.frames.is_empty() {
+            self.frames.push(HashMap::new());
+        }
+        self.frames.last_mut().unwrap().extend(cells);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The viewport, or the bounds of every cell in every frame.
+    pub fn bounds(&self) -> Option<Bounds> {
+        if self.viewport.is_some() {
+            return self.viewport;
+        }
+        let mut cells = self.frames.iter().flat_map(|frame| frame.keys());
+        let first = *cells.next()?;
+        Some(cells.fold((first, first), |(lo, hi), c| {
+            (
+                Coord::new((lo.x.min(c.x), lo.y.min(c.y))),
+                Coord::new((hi.x.max(c.x), hi.y.max(c.y))),
+            )
+        }))
+    }
+
+    /// Draws frame `i`, or `None` if there is no such frame or nothing to draw.
+    pub fn image(&self, i: usize) -> Option<Image> {
+        let frame = self.frames.get(i)?;
+        let (bottom_left, top_right) = self.bounds()?;
+        let columns = (top_right.x - bottom_left.x + 1) as u32;
+        let rows = (top_right.y - bottom_left.y + 1) as u32;
+        let mut image = Image::new(columns * self.scale, rows * self.scale, self.background);
+        for (pos, &colour) in frame {
+            if !pos.is_within(&(bottom_left, top_right)) {
+                continue;
+            }
+            let column = (pos.x - bottom_left.x) as u32;
+            let row = match self.y_axis {
+                YAxis::Down => (pos.y - bottom_left.y) as u32,
+                YAxis::Up => (top_right.y - pos.y) as u32,
+            };
+            for dy in 0..self.scale {
+                for dx in 0..self.scale {
+                    image.set(column * self.scale + dx, row * self.scale + dy, colour);
+                }
+            }
+        }
+        Some(image)
+    }
+
+    /// Saves each frame as `0000.png`, `0001.png`, ... in `dir`, returning the paths.
+    pub fn save_pngs(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+        (0..self.len())
+            .filter_map(|i| Some((i, self.image(i)?)))
+            .map(|(i, image)| {
+                let path = dir.join(format!("{i:04}.png"));
+                image.save_png(&path)?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    pub fn write_gif(&self, w: impl Write) -> io::Result<()> {
+        let Some(first) = self.image(0) else {
+            return Err(io::Error::other("there are no frames to save"));
+        };
+        let width = u16::try_from(first.width).map_err(io::Error::other)?;
+        let height = u16::try_from(first.height).map_err(io::Error::other)?;
+        let delay = u16::try_from(self.delay.as_millis() / 10).unwrap_or(u16::MAX);
+
+        // Puzzles rarely use many colours, so one exact palette usually covers every
+        // frame. Otherwise each frame gets a quantized palette of its own.
+        let mut palette = vec![self.background];
+        for colour in self.frames.iter().flat_map(|frame| frame.values()) {
+            if palette.len() <= 256 && !palette.contains(colour) {
+                palette.push(*colour);
+            }
+        }
+        let exact = palette.len() <= 256;
+        let global_palette = if exact { palette.concat() } else { vec![] };
+
+        let mut encoder =
+            gif::Encoder::new(w, width, height, &global_palette).map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        for image in (0..self.len()).filter_map(|i| self.image(i)) {
+            let mut frame = if exact {
+                let indices = image
+                    .pixels
+                    .chunks(3)
+                    .map(|pixel| palette.iter().position(|c| c == pixel).unwrap() as u8)
+                    .collect::<Vec<u8>>();
+                gif::Frame {
+                    width,
+                    height,
+                    buffer: Cow::Owned(indices),
+                    ..gif::Frame::default()
+                }
+            } else {
+                gif::Frame::from_rgb_speed(width, height, &image.pixels, 10)
+            };
+            frame.delay = delay;
+            encoder.write_frame(&frame).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+
+    pub fn save_gif(&self, path: &Path) -> io::Result<()> {
+        self.write_gif(BufWriter::new(File::create(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HashGrid;
+
+    const RED: Rgb = [255, 0, 0];
+
+    fn falling_block() -> Animation {
+        let mut grid = HashGrid::new();
+        grid.set(Coord::new((0, 2)), '#');
+        grid.set(Coord::new((1, 2)), '#');
+        let mut animation = Animation::new().with_scale(2);
+        for y in 0..2 {
+            animation.capture(&grid, |_| Some(WHITE));
+            animation.overlay([(Coord::new((1, y)), RED)]);
+        }
+        animation
+    }
+
+    #[test]
+    fn test_image() {
+        let animation = falling_block();
+        assert_eq!(animation.len(), 2);
+        assert_eq!(
+            animation.bounds(),
+            Some((Coord::new((0, 0)), Coord::new((1, 2))))
+        );
+
+        let image = animation.image(1).unwrap();
+        assert_eq!((image.width, image.height), (4, 6));
+        assert_eq!(image.get(0, 0), BLACK);
+        assert_eq!(image.get(3, 3), RED);
+        assert_eq!(image.get(0, 5), WHITE);
+        assert!(animation.image(2).is_none());
+
+        let flipped = falling_block().with_y_axis(YAxis::Up).image(1).unwrap();
+        assert_eq!(flipped.get(0, 0), WHITE);
+        assert_eq!(flipped.get(3, 2), RED);
+    }
+
+    #[test]
+    fn test_encode() {
+        let animation = falling_block();
+
+        let mut png = vec![];
+        animation.image(0).unwrap().write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+
+        let mut gif = vec![];
+        animation.write_gif(&mut gif).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+        assert!(Animation::new().write_gif(vec![]).is_err());
+    }
+}
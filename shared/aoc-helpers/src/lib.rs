@@ -0,0 +1,1674 @@
+use int_enum::IntEnum;
+use std::collections::{BTreeMap, HashMap};
+use std::ops;
+
+use num::{PrimInt, Signed};
+use parse_display::{Display, FromStr};
+
+pub mod bitset;
+pub mod cycle;
+pub mod expr;
+pub mod graph;
+pub mod image;
+pub mod region;
+pub mod render;
+pub mod search;
+pub mod timed;
+
+/// A 2D point. `x` goes right and `y` goes down, the way maps are drawn.
+#[derive(Debug, Display, FromStr, Hash, Eq, PartialEq, Copy, Clone)]
+#[display("{x},{y}")]
+pub struct Coord<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Display, FromStr, Hash, Eq, PartialEq, Copy, Clone)]
+#[display("{x},{y},{z}")]
+pub struct Point3<T = i32> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// The arithmetic shared by the point types, written once per dimension count.
+macro_rules! impl_point {
+    ($point:ident { $($field:ident),+ }) => {
+        impl<T: PrimInt + Signed> $point<T> {
+            pub fn manhattan(&self, other: &Self) -> T {
+                T::zero() $(+ (self.$field - other.$field).abs())+
+            }
+
+            pub fn chebyshev(&self, other: &Self) -> T {
+                T::zero() $(.max((self.$field - other.$field).abs()))+
+            }
+
+            /// -1, 0 or 1 along each axis.
+            pub fn signum(&self) -> Self {
+                $point { $($field: self.$field.signum()),+ }
+            }
+
+            /// One step towards `target`, moving diagonally if need be.
+            pub fn step_towards(&self, target: &Self) -> Self {
+                *self + (*target - *self).signum()
+            }
+        }
+
+        impl<T: PrimInt> $point<T> {
+            /// Converts to another integer type, if every coordinate fits.
+            pub fn cast<U: PrimInt>(&self) -> Option<$point<U>> {
+                Some($point { $($field: U::from(self.$field)?),+ })
+            }
+        }
+
+        impl<T> From<($(impl_point!(@ty T $field)),+)> for $point<T> {
+            fn from(($($field),+): ($(impl_point!(@ty T $field)),+)) -> Self {
+                $point { $($field),+ }
+            }
+        }
+
+        impl<T: PrimInt> ops::Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $point { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: PrimInt> ops::Add<&$point<T>> for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: &Self) -> Self {
+                self + *rhs
+            }
+        }
+
+        impl<T: PrimInt> ops::AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: PrimInt> ops::Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $point { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: PrimInt> ops::Sub<&$point<T>> for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: &Self) -> Self {
+                self - *rhs
+            }
+        }
+
+        impl<T: PrimInt> ops::SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<T: PrimInt> ops::Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                $point { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl<T: PrimInt + Signed> ops::Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+    };
+    (@ty $t:ident $field:ident) => { $t };
+}
+
+impl_point!(Coord { x, y });
+impl_point!(Point3 { x, y, z });
+
+impl<T: PrimInt + Signed> Coord<T> {
+    /// Rotates a quarter turn clockwise around the origin (as drawn, with `y` down),
+    /// so right becomes down.
+    pub fn rotate_cw(&self) -> Self {
+        Coord {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    pub fn rotate_ccw(&self) -> Self {
+        Coord {
+            x: self.y,
+            y: -self.x,
+        }
+    }
+}
+
+impl<T: PrimInt + Signed> Point3<T> {
+    /// Rotates a quarter turn around the `x` axis, counterclockwise looking down it.
+    pub fn rotate_x(&self) -> Self {
+        Point3 {
+            x: self.x,
+            y: -self.z,
+            z: self.y,
+        }
+    }
+
+    pub fn rotate_y(&self) -> Self {
+        Point3 {
+            x: self.z,
+            y: self.y,
+            z: -self.x,
+        }
+    }
+
+    pub fn rotate_z(&self) -> Self {
+        Point3 {
+            x: -self.y,
+            y: self.x,
+            z: self.z,
+        }
+    }
+
+    /// The six points that share a face with this one.
+    pub fn neighbors6(&self) -> impl Iterator<Item = Self> {
+        let (zero, one) = (T::zero(), T::one());
+        let pos = *self;
+        [
+            (one, zero, zero),
+            (-one, zero, zero),
+            (zero, one, zero),
+            (zero, -one, zero),
+            (zero, zero, one),
+            (zero, zero, -one),
+        ]
+        .into_iter()
+        .map(move |offset| pos + Point3::from(offset))
+    }
+}
+
+pub trait IntoCoord {
+    fn into_coord(self) -> Coord;
+}
+
+impl IntoCoord for (i32, i32) {
+    fn into_coord(self) -> Coord {
+        Coord {
+            x: self.0,
+            y: self.1,
+        }
+    }
+}
+
+impl IntoCoord for (u32, u32) {
+    fn into_coord(self) -> Coord {
+        Coord {
+            x: self.0 as i32,
+            y: self.1 as i32,
+        }
+    }
+}
+
+impl IntoCoord for (usize, usize) {
+    fn into_coord(self) -> Coord {
+        Coord {
+            x: self.0 as i32,
+            y: self.1 as i32,
+        }
+    }
+}
+
+impl IntoCoord for (isize, isize) {
+    fn into_coord(self) -> Coord {
+        Coord {
+            x: self.0 as i32,
+            y: self.1 as i32,
+        }
+    }
+}
+
+/// Inclusive bottom left and top right corners, as returned by [`Grid::bounds`].
+pub type Bounds = (Coord, Coord);
+
+impl Coord {
+    // Manhatten size
+    pub fn man_size(&self) -> i32 {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn new<T>(data: T) -> Self
+    where
+        T: IntoCoord,
+    {
+        data.into_coord()
+    }
+
+    pub fn is_within(&self, (bottom_left, top_right): &Bounds) -> bool {
+        (bottom_left.x..=top_right.x).contains(&self.x)
+            && (bottom_left.y..=top_right.y).contains(&self.y)
+    }
+
+    /// The orthogonal neighbors, in [`Direction::ALL`] order.
+    pub fn neighbors4(&self) -> impl Iterator<Item = Coord> {
+        let pos = *self;
+        Direction::ALL.into_iter().map(move |dir| pos + dir)
+    }
+
+    /// The orthogonal and diagonal neighbors, in [`Dir8::ALL`] order.
+    pub fn neighbors8(&self) -> impl Iterator<Item = Coord> {
+        let pos = *self;
+        Dir8::ALL.into_iter().map(move |dir| pos + dir)
+    }
+
+    /// The cells around a hex cell, in [`HexDir::ALL`] order.
+    pub fn hex_neighbors(&self) -> impl Iterator<Item = Coord> {
+        let pos = *self;
+        HexDir::ALL.into_iter().map(move |dir| pos + dir)
+    }
+
+    /// The number of [`HexDir`] steps to `other`.
+    pub fn hex_distance(&self, other: &Coord) -> i32 {
+        let (dx, dy) = (self.x - other.x, self.y - other.y);
+        (dx.abs() + dy.abs() + (dx + dy).abs()) / 2
+    }
+
+    /// The orthogonal neighbors that are inside `bounds`.
+    pub fn neighbors_in(&self, bounds: &Bounds) -> impl Iterator<Item = Coord> {
+        self.directions_in(bounds).map(|(_, pos)| pos)
+    }
+
+    /// The directions that can be stepped in without leaving `bounds`, along with
+    /// where each step ends up.
+    pub fn directions_in(&self, bounds: &Bounds) -> impl Iterator<Item = (Direction, Coord)> {
+        let (pos, bounds) = (*self, *bounds);
+        Direction::ALL
+            .into_iter()
+            .map(move |dir| (dir, pos + dir))
+            .filter(move |(_, next)| next.is_within(&bounds))
+    }
+}
+
+impl ops::Add<(i32, i32)> for Coord {
+    type Output = Self;
+
+    fn add(self, rhs: (i32, i32)) -> Self::Output {
+        Coord {
+            x: self.x + rhs.0,
+            y: self.y + rhs.1,
+        }
+    }
+}
+
+impl ops::Add<(i32, i32)> for &Coord {
+    type Output = Coord;
+
+    fn add(self, rhs: (i32, i32)) -> Self::Output {
+        Coord {
+            x: self.x + rhs.0,
+            y: self.y + rhs.1,
+        }
+    }
+}
+
+impl ops::Sub<(i32, i32)> for Coord {
+    type Output = Self;
+
+    fn sub(self, rhs: (i32, i32)) -> Self::Output {
+        Coord {
+            x: self.x - rhs.0,
+            y: self.y - rhs.1,
+        }
+    }
+}
+
+/// Operations shared by the grid representations, so a solution can switch between
+/// [`HashGrid`] and [`VecGrid`] by changing a type alias.
+pub trait Grid<T> {
+    fn get(&self, pos: &Coord) -> Option<&T>;
+    fn set(&mut self, pos: Coord, value: T);
+    fn delete(&mut self, pos: &Coord);
+
+    /// The bottom left and top right corners (inclusive) of the cells that are set.
+    fn bounds(&self) -> (Coord, Coord);
+
+    /// Every cell that is set, in no particular order.
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Coord, &'a T)>
+    where
+        T: 'a;
+
+    fn contains(&self, pos: &Coord) -> bool {
+        self.get(pos).is_some()
+    }
+
+    /// Prints every cell within [`Grid::bounds`]. See [`render::Renderer`] for drawing
+    /// part of a grid, or other things on top of it.
+    fn print(&self, f: impl Fn(Option<&T>) -> String) {
+        println!("{}", self.render(f));
+    }
+
+    fn render(&self, f: impl Fn(Option<&T>) -> String) -> String {
+        let (bottom_left, top_right) = self.bounds();
+        let mut s = String::new();
+        for y in bottom_left.y..=top_right.y {
+            for x in bottom_left.x..=top_right.x {
+                s += &f(self.get(&Coord { x, y }));
+            }
+            s += "\n";
+        }
+        s
+    }
+
+    /// The cells of row `y` from left to right, across the grid's bounds.
+    fn row<'a>(&'a self, y: i32) -> impl Iterator<Item = Option<&'a T>>
+    where
+        T: 'a,
+    {
+        let (bottom_left, top_right) = self.bounds();
+        (bottom_left.x..=top_right.x).map(move |x| self.get(&Coord { x, y }))
+    }
+
+    /// The cells of column `x` from top to bottom, across the grid's bounds.
+    fn column<'a>(&'a self, x: i32) -> impl Iterator<Item = Option<&'a T>>
+    where
+        T: 'a,
+    {
+        let (bottom_left, top_right) = self.bounds();
+        (bottom_left.y..=top_right.y).map(move |y| self.get(&Coord { x, y }))
+    }
+
+    /// A new grid with every cell moved to `f(pos)`.
+    fn transform(&self, f: impl Fn(Coord) -> Coord) -> Self
+    where
+        Self: Sized + Default,
+        T: Clone,
+    {
+        let mut grid = Self::default();
+        for (pos, value) in self.iter() {
+            grid.set(f(pos), value.clone());
+        }
+        grid
+    }
+
+    /// Turns the grid a quarter turn clockwise (as drawn, with `y` going down) about
+    /// its top left corner, which stays where it is.
+    fn rotate_cw(&self) -> Self
+    where
+        Self: Sized + Default,
+        T: Clone,
+    {
+        let (lo, hi) = self.bounds();
+        self.transform(|pos| Coord::new((lo.x + hi.y - pos.y, lo.y + pos.x - lo.x)))
+    }
+
+    fn rotate_ccw(&self) -> Self
+    where
+        Self: Sized + Default,
+        T: Clone,
+    {
+        let (lo, hi) = self.bounds();
+        self.transform(|pos| Coord::new((lo.x + pos.y - lo.y, lo.y + hi.x - pos.x)))
+    }
+
+    fn rotate_180(&self) -> Self
+    where
+        Self: Sized + Default,
+        T: Clone,
+    {
+        let (lo, hi) = self.bounds();
+        self.transform(|pos| Coord::new((lo.x + hi.x - pos.x, lo.y + hi.y - pos.y)))
+    }
+
+    /// Mirrors the grid left to right.
+    fn flip_horizontal(&self) -> Self
+    where
+        Self: Sized + Default,
+        T: Clone,
+    {
+        let (lo, hi) = self.bounds();
+        self.transform(|pos| Coord::new((lo.x + hi.x - pos.x, pos.y)))
+    }
+
+    /// Mirrors the grid top to bottom.
+    fn flip_vertical(&self) -> Self
+    where
+        Self: Sized + Default,
+        T: Clone,
+    {
+        let (lo, hi) = self.bounds();
+        self.transform(|pos| Coord::new((pos.x, lo.y + hi.y - pos.y)))
+    }
+
+    /// Swaps rows and columns, mirroring the grid along its top left to bottom right
+    /// diagonal.
+    fn transpose(&self) -> Self
+    where
+        Self: Sized + Default,
+        T: Clone,
+    {
+        let (lo, _) = self.bounds();
+        self.transform(|pos| Coord::new((lo.x + pos.y - lo.y, lo.y + pos.x - lo.x)))
+    }
+
+    /// The cells inside `bounds`, moved so that `bounds`' top left corner is `0,0`.
+    fn sub_grid(&self, (bottom_left, top_right): &Bounds) -> Self
+    where
+        Self: Sized + Default,
+        T: Clone,
+    {
+        let mut grid = Self::default();
+        for y in bottom_left.y..=top_right.y {
+            for x in bottom_left.x..=top_right.x {
+                if let Some(value) = self.get(&Coord { x, y }) {
+                    grid.set(Coord::new((x, y)) - *bottom_left, value.clone());
+                }
+            }
+        }
+        grid
+    }
+
+    /// Copies the cells of `other` in, with `other`'s top left corner at `at`.
+    /// Cells that are empty in `other` are left alone.
+    fn paste(&mut self, other: &impl Grid<T>, at: Coord)
+    where
+        T: Clone,
+    {
+        let (other_origin, _) = other.bounds();
+        for (pos, value) in other.iter() {
+            self.set(pos - other_origin + at, value.clone());
+        }
+    }
+}
+
+/// Where the marker characters (like `S` and `E`) were found when parsing a map.
+pub type Markers = HashMap<char, Coord>;
+
+/// Walks a character map with `x` going right and `y` going down from `0,0` at the
+/// top left, calling `set` for every cell that `f` maps to something.
+fn parse_char_map<T>(
+    input: &str,
+    markers: &str,
+    mut f: impl FnMut(char) -> Option<T>,
+    mut set: impl FnMut(Coord, T),
+) -> Markers {
+    let mut found = Markers::new();
+    for (y, line) in input.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            let pos = Coord::new((x, y));
+            if markers.contains(c) {
+                found.insert(c, pos);
+            }
+            if let Some(value) = f(c) {
+                set(pos, value);
+            }
+        }
+    }
+    found
+}
+
+/// How many cells are set in each row and column, which is all it takes to keep a
+/// grid's bounds up to date as cells come and go.
+#[derive(Default, Clone)]
+struct Occupancy {
+    columns: BTreeMap<i32, usize>,
+    rows: BTreeMap<i32, usize>,
+    len: usize,
+}
+
+impl Occupancy {
+    fn add(&mut self, pos: &Coord) {
+        *self.columns.entry(pos.x).or_default() += 1;
+        *self.rows.entry(pos.y).or_default() += 1;
+        self.len += 1;
+    }
+
+    /// Returns whether a row or column emptied, which is the only time the bounds change.
+    fn remove(&mut self, pos: &Coord) -> bool {
+        self.len -= 1;
+        // Both counts have to be updated, hence `|` rather than `||`
+        remove_one(&mut self.columns, pos.x) | remove_one(&mut self.rows, pos.y)
+    }
+
+    /// `bottom_left`, `top_right`, `width` and `height` as the grids keep them.
+    fn extent(&self) -> (Coord, Coord, usize, usize) {
+        let first_last = |counts: &BTreeMap<i32, usize>| {
+            Some((*counts.first_key_value()?.0, *counts.last_key_value()?.0))
+        };
+        match (first_last(&self.columns), first_last(&self.rows)) {
+            (Some((left, right)), Some((bottom, top))) => (
+                Coord { x: left, y: bottom },
+                Coord { x: right, y: top },
+                (right - left + 1) as usize,
+                (top - bottom + 1) as usize,
+            ),
+            _ => (Coord { x: 0, y: 0 }, Coord { x: 0, y: 0 }, 0, 0),
+        }
+    }
+}
+
+/// Decrements the count for `key`, returning whether that was the last one.
+fn remove_one(counts: &mut BTreeMap<i32, usize>, key: i32) -> bool {
+    let Some(count) = counts.get_mut(&key) else {
+        return false;
+    };
+    *count -= 1;
+    if *count == 0 {
+        counts.remove(&key);
+        return true;
+    }
+    false
+}
+
+/// A sparse grid. `width`, `height`, `bottom_left` and `top_right` always describe
+/// the cells that are set, and are all zero when the grid is empty.
+///
+/// The bounds are kept up to date by counting the cells in every row and column, so
+/// go through `set` and `delete` rather than changing `data` directly.
+#[derive(Clone)]
+pub struct HashGrid<T> {
+    pub data: HashMap<Coord, T>,
+
+    pub width: usize,
+    pub height: usize,
+
+    pub bottom_left: Coord,
+    pub top_right: Coord,
+
+    occupancy: Occupancy,
+}
+
+impl<T> Default for HashGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HashGrid<T> {
+    pub fn new() -> Self {
+        HashGrid {
+            data: HashMap::new(),
+            width: 0,
+            height: 0,
+            bottom_left: Coord { x: 0, y: 0 },
+            top_right: Coord { x: 0, y: 0 },
+            occupancy: Occupancy::default(),
+        }
+    }
+
+    /// Parses a character map. `f` turns each character into a cell, or `None` to
+    /// leave it empty; use [`HashGrid::from_str_map`] to also find marker characters.
+    pub fn parse(input: &str, f: impl FnMut(char) -> Option<T>) -> Self {
+        Self::from_str_map(input, "", f).0
+    }
+
+    /// Like [`HashGrid::parse`], but also returns where each of the `markers`
+    /// characters is. Markers are still passed to `f` like any other character.
+    pub fn from_str_map(
+        input: &str,
+        markers: &str,
+        f: impl FnMut(char) -> Option<T>,
+    ) -> (Self, Markers) {
+        let mut grid = Self::new();
+        let markers = parse_char_map(input, markers, f, |pos, value| grid.set(pos, value));
+        (grid, markers)
+    }
+
+    pub fn set(&mut self, pos: Coord, value: T) {
+        if self.data.insert(pos, value).is_none() {
+            self.occupancy.add(&pos);
+            self.update_bounds();
+        }
+    }
+
+    pub fn get(&self, pos: &Coord) -> Option<&T> {
+        self.data.get(pos)
+    }
+
+    pub fn delete(&mut self, pos: &Coord) {
+        if self.data.remove(pos).is_some() && self.occupancy.remove(pos) {
+            self.update_bounds();
+        }
+    }
+
+    fn update_bounds(&mut self) {
+        (self.bottom_left, self.top_right, self.width, self.height) = self.occupancy.extent();
+    }
+}
+
+impl<T> Grid<T> for HashGrid<T> {
+    fn get(&self, pos: &Coord) -> Option<&T> {
+        HashGrid::get(self, pos)
+    }
+
+    fn set(&mut self, pos: Coord, value: T) {
+        HashGrid::set(self, pos, value)
+    }
+
+    fn delete(&mut self, pos: &Coord) {
+        HashGrid::delete(self, pos)
+    }
+
+    fn bounds(&self) -> (Coord, Coord) {
+        (self.bottom_left, self.top_right)
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Coord, &'a T)>
+    where
+        T: 'a,
+    {
+        self.data.iter().map(|(pos, value)| (*pos, value))
+    }
+}
+
+/// A dense grid backed by a `Vec`. The storage grows to cover whatever is set, and
+/// can start anywhere (see [`VecGrid::with_bounds`]), so negative coordinates are fine.
+///
+/// `width`, `height`, `bottom_left` and `top_right` describe the cells that are set,
+/// and are kept up to date the same way as for [`HashGrid`].
+#[derive(Clone)]
+pub struct VecGrid<T> {
+    data: Vec<Option<T>>,
+    origin: Coord,
+    cols: usize,
+    rows: usize,
+
+    pub width: usize,
+    pub height: usize,
+
+    pub bottom_left: Coord,
+    pub top_right: Coord,
+
+    occupancy: Occupancy,
+}
+
+impl<T> Default for VecGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> VecGrid<T> {
+    pub fn new() -> Self {
+        VecGrid {
+            data: Vec::new(),
+            origin: Coord { x: 0, y: 0 },
+            cols: 0,
+            rows: 0,
+            width: 0,
+            height: 0,
+            bottom_left: Coord { x: 0, y: 0 },
+            top_right: Coord { x: 0, y: 0 },
+            occupancy: Occupancy::default(),
+        }
+    }
+
+    /// An empty grid with room for everything from `bottom_left` to `top_right`.
+    pub fn with_bounds(bottom_left: Coord, top_right: Coord) -> Self {
+        let mut grid = Self::new();
+        grid.reserve(bottom_left, top_right);
+        grid
+    }
+
+    /// Parses a character map. `f` turns each character into a cell, or `None` to
+    /// leave it empty; use [`VecGrid::from_str_map`] to also find marker characters.
+    pub fn parse(input: &str, f: impl FnMut(char) -> Option<T>) -> Self {
+        Self::from_str_map(input, "", f).0
+    }
+
+    /// Like [`VecGrid::parse`], but also returns where each of the `markers`
+    /// characters is. Markers are still passed to `f` like any other character.
+    pub fn from_str_map(
+        input: &str,
+        markers: &str,
+        f: impl FnMut(char) -> Option<T>,
+    ) -> (Self, Markers) {
+        let cols = input.lines().map(|line| line.chars().count()).max();
+        let rows = input.lines().count();
+        let mut grid = match cols {
+            Some(cols) if cols > 0 => {
+                Self::with_bounds(Coord::new((0, 0)), Coord::new((cols - 1, rows - 1)))
+            }
+            _ => Self::new(),
+        };
+        let markers = parse_char_map(input, markers, f, |pos, value| grid.set(pos, value));
+        (grid, markers)
+    }
+
+    fn index(&self, pos: &Coord) -> Option<usize> {
+        let x = usize::try_from(pos.x - self.origin.x).ok()?;
+        let y = usize::try_from(pos.y - self.origin.y).ok()?;
+        (x < self.cols && y < self.rows).then_some(y * self.cols + x)
+    }
+
+    fn position(&self, index: usize) -> Coord {
+        Coord {
+            x: self.origin.x + (index % self.cols) as i32,
+            y: self.origin.y + (index / self.cols) as i32,
+        }
+    }
+
+    /// Grows the storage to cover `bottom_left` to `top_right`, keeping what's set.
+    fn reserve(&mut self, mut bottom_left: Coord, mut top_right: Coord) {
+        if !self.data.is_empty() {
+            bottom_left.x = bottom_left.x.min(self.origin.x);
+            bottom_left.y = bottom_left.y.min(self.origin.y);
+            top_right.x = top_right.x.max(self.origin.x + self.cols as i32 - 1);
+            top_right.y = top_right.y.max(self.origin.y + self.rows as i32 - 1);
+        }
+        let old = std::mem::take(&mut self.data);
+        let (old_origin, old_cols) = (self.origin, self.cols);
+
+        self.origin = bottom_left;
+        self.cols = (top_right.x - bottom_left.x + 1) as usize;
+        self.rows = (top_right.y - bottom_left.y + 1) as usize;
+        self.data = (0..self.cols * self.rows).map(|_| None).collect();
+
+        for (index, value) in old.into_iter().enumerate() {
+            if value.is_some() {
+                let pos = Coord {
+                    x: old_origin.x + (index % old_cols) as i32,
+                    y: old_origin.y + (index / old_cols) as i32,
+                };
+                let index = self.index(&pos).unwrap();
+                self.data[index] = value;
+            }
+        }
+    }
+
+    pub fn get(&self, pos: &Coord) -> Option<&T> {
+        self.data[self.index(pos)?].as_ref()
+    }
+
+    pub fn get_mut(&mut self, pos: &Coord) -> Option<&mut T> {
+        let index = self.index(pos)?;
+        self.data[index].as_mut()
+    }
+
+    pub fn set(&mut self, pos: Coord, value: T) {
+        if self.data.is_empty() {
+            self.reserve(pos, pos);
+        } else if self.index(&pos).is_none() {
+            // Grow by at least the current size on the side we ran out of room, so
+            // filling a grid cell by cell doesn't reallocate every time
+            let (cols, rows) = (self.cols as i32, self.rows as i32);
+            let mut bottom_left = self.origin;
+            let mut top_right = Coord {
+                x: self.origin.x + cols - 1,
+                y: self.origin.y + rows - 1,
+            };
+            if pos.x < bottom_left.x {
+                bottom_left.x = pos.x.min(bottom_left.x - cols);
+            }
+            if pos.x > top_right.x {
+                top_right.x = pos.x.max(top_right.x + cols);
+            }
+            if pos.y < bottom_left.y {
+                bottom_left.y = pos.y.min(bottom_left.y - rows);
+            }
+            if pos.y > top_right.y {
+                top_right.y = pos.y.max(top_right.y + rows);
+            }
+            self.reserve(bottom_left, top_right);
+        }
+        let index = self.index(&pos).unwrap();
+        if self.data[index].replace(value).is_none() {
+            self.occupancy.add(&pos);
+            self.update_bounds();
+        }
+    }
+
+    pub fn delete(&mut self, pos: &Coord) {
+        let Some(index) = self.index(pos) else {
+            return;
+        };
+        if self.data[index].take().is_some() && self.occupancy.remove(pos) {
+            self.update_bounds();
+        }
+    }
+
+    /// Row `y` from `bottom_left.x` to `top_right.x`, without copying it.
+    pub fn row_slice(&self, y: i32) -> Option<&[Option<T>]> {
+        let start = self.index(&Coord::new((self.bottom_left.x, y)))?;
+        Some(&self.data[start..start + self.width])
+    }
+
+    /// Every row from top to bottom, as in [`VecGrid::row_slice`].
+    pub fn row_slices(&self) -> impl Iterator<Item = &[Option<T>]> {
+        (self.bottom_left.y..=self.top_right.y).filter_map(|y| self.row_slice(y))
+    }
+
+    /// Column `x` from `bottom_left.y` to `top_right.y`, without copying it.
+    pub fn column_slice(&self, x: i32) -> Option<ColumnSlice<'_, T>> {
+        let start = self.index(&Coord::new((x, self.bottom_left.y)))?;
+        Some(ColumnSlice {
+            data: &self.data[start..],
+            stride: self.cols,
+            len: self.height,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.occupancy.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.data
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| Some((self.position(index), value.as_ref()?)))
+    }
+
+    fn update_bounds(&mut self) {
+        (self.bottom_left, self.top_right, self.width, self.height) = self.occupancy.extent();
+    }
+}
+
+/// A column of a [`VecGrid`], borrowed in place. Columns aren't contiguous, so this
+/// steps through the grid's storage a row at a time, but otherwise it can be sliced
+/// and iterated like the rows from [`VecGrid::row_slice`].
+#[derive(Debug)]
+pub struct ColumnSlice<'a, T> {
+    data: &'a [Option<T>],
+    stride: usize,
+    len: usize,
+}
+
+impl<T> Clone for ColumnSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ColumnSlice<'_, T> {}
+
+impl<'a, T> ColumnSlice<'a, T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The `i`th cell from the top, like indexing a slice.
+    pub fn get(&self, i: usize) -> Option<&'a Option<T>> {
+        (i < self.len).then(|| &self.data[i * self.stride])
+    }
+
+    /// The cells in `range`, like slicing a slice. Panics if `range` is out of bounds.
+    pub fn slice(&self, range: impl ops::RangeBounds<usize>) -> Self {
+        let start = match range.start_bound() {
+            ops::Bound::Included(&i) => i,
+            ops::Bound::Excluded(&i) => i + 1,
+            ops::Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            ops::Bound::Included(&i) => i + 1,
+            ops::Bound::Excluded(&i) => i,
+            ops::Bound::Unbounded => self.len,
+        };
+        assert!(
+            start <= end && end <= self.len,
+            "range {start}..{end} out of bounds for a column of {}",
+            self.len
+        );
+        ColumnSlice {
+            data: self.data.get(start * self.stride..).unwrap_or_default(),
+            stride: self.stride,
+            len: end - start,
+        }
+    }
+
+    /// The cells from top to bottom.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'a Option<T>> + ExactSizeIterator {
+        self.data.iter().step_by(self.stride).take(self.len)
+    }
+}
+
+impl<T> Grid<T> for VecGrid<T> {
+    fn get(&self, pos: &Coord) -> Option<&T> {
+        VecGrid::get(self, pos)
+    }
+
+    fn set(&mut self, pos: Coord, value: T) {
+        VecGrid::set(self, pos, value)
+    }
+
+    fn delete(&mut self, pos: &Coord) {
+        VecGrid::delete(self, pos)
+    }
+
+    fn bounds(&self) -> (Coord, Coord) {
+        (self.bottom_left, self.top_right)
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Coord, &'a T)>
+    where
+        T: 'a,
+    {
+        VecGrid::iter(self)
+    }
+}
+
+/// The turning, reversing and stepping shared by the direction sets. Variants must be
+/// listed clockwise and numbered from 0, which is what `rotate_cw` relies on.
+macro_rules! impl_direction {
+    ($dir:ident [$($variant:ident),+]) => {
+        impl $dir {
+            /// Every direction, clockwise.
+            pub const ALL: [$dir; impl_direction!(@count $($variant)+)] = [$($dir::$variant),+];
+
+            /// Turns `n` steps clockwise.
+            pub fn rotate_cw(&self, n: usize) -> $dir {
+                Self::ALL[(*self as usize + n) % Self::ALL.len()]
+            }
+
+            /// Turns `n` steps anticlockwise.
+            pub fn rotate_ccw(&self, n: usize) -> $dir {
+                let len = Self::ALL.len();
+                Self::ALL[(*self as usize + len - n % len) % len]
+            }
+
+            pub fn reverse(&self) -> $dir {
+                self.rotate_cw(Self::ALL.len() / 2)
+            }
+        }
+
+        impl ops::Add<$dir> for Coord {
+            type Output = Coord;
+
+            fn add(self, rhs: $dir) -> Coord {
+                self + rhs.offsets()
+            }
+        }
+
+        impl ops::Add<$dir> for &Coord {
+            type Output = Coord;
+
+            fn add(self, rhs: $dir) -> Coord {
+                self + rhs.offsets()
+            }
+        }
+
+        impl ops::Add<&$dir> for &Coord {
+            type Output = Coord;
+
+            fn add(self, rhs: &$dir) -> Coord {
+                self + rhs.offsets()
+            }
+        }
+
+        impl ops::AddAssign<$dir> for Coord {
+            fn add_assign(&mut self, rhs: $dir) {
+                *self = *self + rhs;
+            }
+        }
+    };
+    (@count $($variant:ident)+) => { 0 $(+ impl_direction!(@one $variant))+ };
+    (@one $variant:ident) => { 1 };
+}
+
+/// The orthogonal directions.
+///
+/// Displays as an arrow, and parses from arrows, `U`/`D`/`L`/`R` or compass letters.
+#[repr(u8)]
+#[derive(IntEnum, Debug, Copy, Clone, Eq, PartialEq, Hash, Display)]
+pub enum Direction {
+    #[display(">")]
+    Right = 0,
+    #[display("v")]
+    Down = 1,
+    #[display("<")]
+    Left = 2,
+    #[display("^")]
+    Up = 3,
+}
+
+impl_direction!(Direction[Right, Down, Left, Up]);
+
+impl Direction {
+    pub fn as_char(&self) -> char {
+        match self {
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Up => '^',
+        }
+    }
+
+    pub fn right(&self) -> Direction {
+        self.rotate_cw(1)
+    }
+
+    pub fn left(&self) -> Direction {
+        self.rotate_ccw(1)
+    }
+
+    pub fn turn_right(&mut self) {
+        *self = self.right();
+    }
+
+    pub fn turn_left(&mut self) {
+        *self = self.left();
+    }
+
+    pub fn offsets(&self) -> (i32, i32) {
+        match self {
+            Direction::Right => (1, 0),
+            Direction::Left => (-1, 0),
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+        }
+    }
+}
+
+impl std::str::FromStr for Direction {
+    type Err = parse_display::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Dir8::from_str(s)? {
+            Dir8::E => Ok(Direction::Right),
+            Dir8::S => Ok(Direction::Down),
+            Dir8::W => Ok(Direction::Left),
+            Dir8::N => Ok(Direction::Up),
+            _ => Err(parse_display::ParseError::with_message(
+                "not an orthogonal direction",
+            )),
+        }
+    }
+}
+
+/// The orthogonal and diagonal directions, named after the compass with north up.
+///
+/// Parses from compass points, `U`/`D`/`L`/`R` (and pairs like `UR`) or arrows,
+/// ignoring case.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Display)]
+pub enum Dir8 {
+    N = 0,
+    NE = 1,
+    E = 2,
+    SE = 3,
+    S = 4,
+    SW = 5,
+    W = 6,
+    NW = 7,
+}
+
+impl_direction!(Dir8[N, NE, E, SE, S, SW, W, NW]);
+
+impl Dir8 {
+    pub fn offsets(&self) -> (i32, i32) {
+        match self {
+            Dir8::N => (0, -1),
+            Dir8::NE => (1, -1),
+            Dir8::E => (1, 0),
+            Dir8::SE => (1, 1),
+            Dir8::S => (0, 1),
+            Dir8::SW => (-1, 1),
+            Dir8::W => (-1, 0),
+            Dir8::NW => (-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        *self as u8 % 2 == 1
+    }
+}
+
+impl From<Direction> for Dir8 {
+    fn from(dir: Direction) -> Self {
+        match dir {
+            Direction::Right => Dir8::E,
+            Direction::Down => Dir8::S,
+            Direction::Left => Dir8::W,
+            Direction::Up => Dir8::N,
+        }
+    }
+}
+
+impl std::str::FromStr for Dir8 {
+    type Err = parse_display::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let dir = match s.to_ascii_uppercase().as_str() {
+            "N" | "U" | "^" => Dir8::N,
+            "NE" | "UR" => Dir8::NE,
+            "E" | "R" | ">" => Dir8::E,
+            "SE" | "DR" => Dir8::SE,
+            "S" | "D" | "V" => Dir8::S,
+            "SW" | "DL" => Dir8::SW,
+            "W" | "L" | "<" => Dir8::W,
+            "NW" | "UL" => Dir8::NW,
+            _ => return Err(parse_display::ParseError::with_message("unknown direction")),
+        };
+        Ok(dir)
+    }
+}
+
+/// The directions on a hex grid of pointy-topped cells, in axial coordinates: `x`
+/// runs east and `y` runs south-east, so north-west is straight up the `y` axis.
+///
+/// Displays and parses as lowercase compass points (`e`, `ne`, ...), the way AoC
+/// writes them.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Display)]
+#[display(style = "lowercase")]
+pub enum HexDir {
+    E = 0,
+    SE = 1,
+    SW = 2,
+    W = 3,
+    NW = 4,
+    NE = 5,
+}
+
+impl_direction!(HexDir[E, SE, SW, W, NW, NE]);
+
+impl HexDir {
+    pub fn offsets(&self) -> (i32, i32) {
+        match self {
+            HexDir::E => (1, 0),
+            HexDir::SE => (0, 1),
+            HexDir::SW => (-1, 1),
+            HexDir::W => (-1, 0),
+            HexDir::NW => (0, -1),
+            HexDir::NE => (1, -1),
+        }
+    }
+
+    /// Parses a run of directions with no separators, like `esenee`.
+    pub fn parse_path(s: &str) -> Result<Vec<HexDir>, parse_display::ParseError> {
+        let mut path = vec![];
+        let mut rest = s.trim();
+        while !rest.is_empty() {
+            let len = if rest.starts_with(['n', 's', 'N', 'S']) {
+                2
+            } else {
+                1
+            };
+            let step = rest.get(..len).ok_or_else(parse_display::ParseError::new)?;
+            path.push(step.parse()?);
+            rest = &rest[len..];
+        }
+        Ok(path)
+    }
+}
+
+impl std::str::FromStr for HexDir {
+    type Err = parse_display::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let dir = match s.to_ascii_lowercase().as_str() {
+            "e" => HexDir::E,
+            "se" => HexDir::SE,
+            "sw" => HexDir::SW,
+            "w" => HexDir::W,
+            "nw" => HexDir::NW,
+            "ne" => HexDir::NE,
+            _ => {
+                return Err(parse_display::ParseError::with_message(
+                    "unknown hex direction",
+                ))
+            }
+        };
+        Ok(dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn fill<G: Grid<char>>(mut grid: G) -> G {
+        for (pos, c) in [((2, 1), 'a'), ((-1, 3), 'b'), ((0, 0), 'c')] {
+            grid.set(Coord::new(pos), c);
+        }
+        grid
+    }
+
+    fn check_grid<G: Grid<char>>(grid: G) {
+        let mut grid = fill(grid);
+        assert_eq!(grid.get(&Coord::new((2, 1))), Some(&'a'));
+        assert_eq!(grid.get(&Coord::new((1, 1))), None);
+        assert_eq!(grid.get(&Coord::new((100, -100))), None);
+        assert_eq!(grid.bounds(), (Coord::new((-1, 0)), Coord::new((2, 3))));
+
+        let mut cells: Vec<_> = grid.iter().map(|(pos, c)| (pos, *c)).collect();
+        cells.sort_by_key(|(_, c)| *c);
+        assert_eq!(
+            cells,
+            vec![
+                (Coord::new((2, 1)), 'a'),
+                (Coord::new((-1, 3)), 'b'),
+                (Coord::new((0, 0)), 'c')
+            ]
+        );
+
+        let render = |c: Option<&char>| c.map_or(".".to_string(), |c| c.to_string());
+        assert_eq!(grid.render(render), ".c..\n...a\n....\nb...\n");
+
+        grid.delete(&Coord::new((-1, 3)));
+        assert!(!grid.contains(&Coord::new((-1, 3))));
+        assert_eq!(grid.bounds(), (Coord::new((0, 0)), Coord::new((2, 1))));
+        assert_eq!(grid.render(render), "c..\n..a\n");
+    }
+
+    #[test]
+    fn test_point_arithmetic() {
+        let a = Coord::new((1, -2));
+        let b = Coord::new((4, 2));
+        assert_eq!(a + b, Coord::new((5, 0)));
+        assert_eq!(b - a, Coord::new((3, 4)));
+        assert_eq!(a * 3, Coord::new((3, -6)));
+        assert_eq!(-a, Coord::new((-1, 2)));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!((b - a).signum(), Coord::new((1, 1)));
+        assert_eq!(a.step_towards(&b), Coord::new((2, -1)));
+        assert_eq!(a.step_towards(&a), a);
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+
+        let wide: Coord<i64> = Coord::from((3_000_000_000, -1));
+        assert_eq!(wide.manhattan(&Coord::from((0, 0))), 3_000_000_001);
+        assert_eq!(wide.cast::<i32>(), None);
+        assert_eq!(a.cast::<i64>(), Some(Coord::from((1i64, -2))));
+        assert_eq!("-3,7".parse::<Coord<i64>>().unwrap(), Coord::from((-3, 7)));
+    }
+
+    #[test]
+    fn test_rotate() {
+        let right = Coord::new((1, 0));
+        let down = right.rotate_cw();
+        assert_eq!(down, Coord::new((0, 1)));
+        assert_eq!(down.rotate_ccw(), right);
+        assert_eq!(right.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), right);
+
+        let p = Point3::from((1, 2, 3));
+        assert_eq!(p.rotate_z(), Point3::from((-2, 1, 3)));
+        assert_eq!(p.rotate_x(), Point3::from((1, -3, 2)));
+        assert_eq!(p.rotate_y(), Point3::from((3, 2, -1)));
+        assert_eq!(p.rotate_x().rotate_x().rotate_x().rotate_x(), p);
+    }
+
+    #[test]
+    fn test_point3() {
+        let p: Point3 = "2,2,2".parse().unwrap();
+        assert_eq!(p.to_string(), "2,2,2");
+        assert_eq!(p.manhattan(&Point3::from((1, 2, 5))), 4);
+        assert_eq!(p.chebyshev(&Point3::from((1, 2, 5))), 3);
+
+        let neighbors: HashSet<Point3> = p.neighbors6().collect();
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.iter().all(|n| n.manhattan(&p) == 1));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let pos = Coord::new((0, 0));
+        assert_eq!(
+            pos.neighbors4().collect::<Vec<_>>(),
+            vec![
+                Coord::new((1, 0)),
+                Coord::new((0, 1)),
+                Coord::new((-1, 0)),
+                Coord::new((0, -1))
+            ]
+        );
+
+        let neighbors8: HashSet<Coord> = pos.neighbors8().collect();
+        assert_eq!(neighbors8.len(), 8);
+        assert!(!neighbors8.contains(&pos));
+        assert!(neighbors8.iter().all(|n| n.x.abs() <= 1 && n.y.abs() <= 1));
+    }
+
+    #[test]
+    fn test_neighbors_in() {
+        let bounds = (Coord::new((0, 0)), Coord::new((2, 1)));
+        assert!(Coord::new((2, 1)).is_within(&bounds));
+        assert!(!Coord::new((3, 1)).is_within(&bounds));
+
+        // Top left corner: only right and down stay inside
+        assert_eq!(
+            Coord::new((0, 0))
+                .directions_in(&bounds)
+                .collect::<Vec<_>>(),
+            vec![
+                (Direction::Right, Coord::new((1, 0))),
+                (Direction::Down, Coord::new((0, 1)))
+            ]
+        );
+        assert_eq!(Coord::new((1, 0)).neighbors_in(&bounds).count(), 3);
+        assert_eq!(
+            Coord::new((1, 1)).neighbors_in(&bounds).collect::<Vec<_>>(),
+            vec![Coord::new((2, 1)), Coord::new((0, 1)), Coord::new((1, 0))]
+        );
+        // Outside the bounds altogether
+        assert_eq!(Coord::new((5, 5)).neighbors_in(&bounds).count(), 0);
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::Up.rotate_cw(1), Direction::Right);
+        assert_eq!(Direction::Up.rotate_ccw(5), Direction::Left);
+        assert_eq!(Direction::Down.reverse(), Direction::Up);
+        assert_eq!(Dir8::N.rotate_cw(3), Dir8::SE);
+        assert_eq!(Dir8::N.rotate_ccw(1), Dir8::NW);
+        assert_eq!(Dir8::NE.reverse(), Dir8::SW);
+        assert_eq!(HexDir::E.reverse(), HexDir::W);
+        assert_eq!(HexDir::NE.rotate_cw(1), HexDir::E);
+
+        // Every direction set steps back to where it started when reversed
+        let origin = Coord::new((0, 0));
+        assert!(Dir8::ALL
+            .iter()
+            .all(|d| origin + *d + d.reverse() == origin));
+        assert!(HexDir::ALL
+            .iter()
+            .all(|d| origin + *d + d.reverse() == origin));
+        assert_eq!(Dir8::ALL.iter().filter(|d| d.is_diagonal()).count(), 4);
+        assert_eq!(Dir8::from(Direction::Left), Dir8::W);
+    }
+
+    #[test]
+    fn test_parse_directions() {
+        for s in ["U", "u", "N", "^"] {
+            assert_eq!(s.parse::<Direction>().unwrap(), Direction::Up);
+            assert_eq!(s.parse::<Dir8>().unwrap(), Dir8::N);
+        }
+        assert_eq!("v".parse::<Direction>().unwrap(), Direction::Down);
+        assert_eq!("R".parse::<Direction>().unwrap(), Direction::Right);
+        assert!("NE".parse::<Direction>().is_err());
+        assert!(".".parse::<Direction>().is_err());
+        assert_eq!("ne".parse::<Dir8>().unwrap(), Dir8::NE);
+        assert_eq!("DL".parse::<Dir8>().unwrap(), Dir8::SW);
+        assert_eq!(Dir8::SE.to_string(), "SE");
+        for dir in Direction::ALL {
+            assert_eq!(dir.to_string().parse::<Direction>().unwrap(), dir);
+        }
+
+        assert_eq!(
+            HexDir::parse_path("esenee").unwrap(),
+            vec![HexDir::E, HexDir::SE, HexDir::NE, HexDir::E]
+        );
+        assert!(HexDir::parse_path("en").is_err());
+        assert!(HexDir::parse_path("x").is_err());
+        assert_eq!(HexDir::NW.to_string(), "nw");
+
+        let end = HexDir::parse_path("nwwswee")
+            .unwrap()
+            .into_iter()
+            .fold(Coord::new((0, 0)), |pos, dir| pos + dir);
+        assert_eq!(end, Coord::new((0, 0)));
+        let far = Coord::new((0, 0)) + HexDir::NE + HexDir::NE + HexDir::E;
+        assert_eq!(far.hex_distance(&Coord::new((0, 0))), 3);
+        assert!(far.hex_neighbors().all(|n| n.hex_distance(&far) == 1));
+    }
+
+    #[test]
+    fn test_hash_grid() {
+        check_grid(HashGrid::new());
+    }
+
+    /// The bounds of a [`HashGrid`] or a [`VecGrid`], flattened for comparing.
+    macro_rules! corners {
+        ($grid:expr) => {
+            (
+                $grid.bottom_left.x,
+                $grid.bottom_left.y,
+                $grid.top_right.x,
+                $grid.top_right.y,
+                $grid.width,
+                $grid.height,
+            )
+        };
+    }
+
+    #[test]
+    fn test_hash_grid_single_cell() {
+        let mut grid = HashGrid::new();
+        assert_eq!(corners!(grid), (0, 0, 0, 0, 0, 0));
+
+        grid.set(Coord::new((3, -2)), ());
+        assert_eq!(corners!(grid), (3, -2, 3, -2, 1, 1));
+
+        grid.delete(&Coord::new((3, -2)));
+        assert_eq!(corners!(grid), (0, 0, 0, 0, 0, 0));
+        assert!(grid.data.is_empty());
+
+        grid.set(Coord::new((-1, 5)), ());
+        assert_eq!(corners!(grid), (-1, 5, -1, 5, 1, 1));
+    }
+
+    #[test]
+    fn test_hash_grid_delete_down_to_one() {
+        let mut grid = HashGrid::new();
+        grid.set(Coord::new((0, 0)), ());
+        grid.set(Coord::new((4, 4)), ());
+
+        grid.delete(&Coord::new((4, 4)));
+        assert_eq!(corners!(grid), (0, 0, 0, 0, 1, 1));
+        grid.delete(&Coord::new((0, 0)));
+        assert_eq!(corners!(grid), (0, 0, 0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_hash_grid_delete_missing() {
+        let mut grid = HashGrid::new();
+        grid.delete(&Coord::new((0, 0)));
+        assert_eq!(corners!(grid), (0, 0, 0, 0, 0, 0));
+
+        grid.set(Coord::new((0, 0)), ());
+        grid.set(Coord::new((2, 2)), ());
+        // On the edge of the bounds, but not set
+        grid.delete(&Coord::new((2, 0)));
+        assert_eq!(corners!(grid), (0, 0, 2, 2, 3, 3));
+    }
+
+    #[test]
+    fn test_hash_grid_overwrite() {
+        let mut grid = HashGrid::new();
+        grid.set(Coord::new((1, 1)), 'a');
+        grid.set(Coord::new((1, 1)), 'b');
+        grid.set(Coord::new((2, 3)), 'c');
+        assert_eq!(grid.get(&Coord::new((1, 1))), Some(&'b'));
+
+        // One delete clears a cell that was set twice
+        grid.delete(&Coord::new((1, 1)));
+        assert_eq!(corners!(grid), (2, 3, 2, 3, 1, 1));
+    }
+
+    #[test]
+    fn test_hash_grid_shared_edges() {
+        let mut grid = HashGrid::new();
+        for pos in [(0, 0), (0, 5), (5, 0), (5, 5), (2, 2)] {
+            grid.set(Coord::new(pos), ());
+        }
+        // Other cells are still on these edges
+        grid.delete(&Coord::new((0, 0)));
+        assert_eq!(corners!(grid), (0, 0, 5, 5, 6, 6));
+
+        grid.delete(&Coord::new((0, 5)));
+        assert_eq!(corners!(grid), (2, 0, 5, 5, 4, 6));
+
+        grid.delete(&Coord::new((5, 0)));
+        assert_eq!(corners!(grid), (2, 2, 5, 5, 4, 4));
+
+        // An interior cell doesn't move the bounds
+        grid.set(Coord::new((3, 3)), ());
+        grid.delete(&Coord::new((3, 3)));
+        assert_eq!(corners!(grid), (2, 2, 5, 5, 4, 4));
+    }
+
+    #[test]
+    fn test_hash_grid_many_deletes() {
+        let mut grid = HashGrid::new();
+        for i in 0..100 {
+            grid.set(Coord::new((i, i % 7)), i);
+        }
+        for i in (0..100).rev() {
+            assert_eq!(grid.top_right.x, i);
+            grid.delete(&Coord::new((i, i % 7)));
+        }
+        assert_eq!(corners!(grid), (0, 0, 0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_vec_grid_deletes() {
+        let mut grid = VecGrid::new();
+        for pos in [(0, 0), (0, 5), (5, 0), (5, 5), (2, 2)] {
+            grid.set(Coord::new(pos), ());
+        }
+        grid.delete(&Coord::new((0, 0)));
+        assert_eq!(corners!(grid), (0, 0, 5, 5, 6, 6));
+        grid.delete(&Coord::new((0, 5)));
+        assert_eq!(corners!(grid), (2, 0, 5, 5, 4, 6));
+        assert_eq!(grid.len(), 3);
+
+        // Deleting what isn't there changes nothing, in or out of the storage
+        grid.delete(&Coord::new((3, 3)));
+        grid.delete(&Coord::new((50, 50)));
+        assert_eq!(grid.len(), 3);
+
+        for pos in [(5, 0), (5, 5), (2, 2)] {
+            grid.delete(&Coord::new(pos));
+        }
+        assert_eq!(corners!(grid), (0, 0, 0, 0, 0, 0));
+        assert!(grid.is_empty());
+
+        grid.set(Coord::new((-1, 5)), ());
+        grid.set(Coord::new((-1, 5)), ());
+        assert_eq!(corners!(grid), (-1, 5, -1, 5, 1, 1));
+        assert_eq!(grid.len(), 1);
+    }
+
+    #[test]
+    fn test_vec_grid() {
+        check_grid(VecGrid::new());
+        check_grid(VecGrid::with_bounds(
+            Coord::new((-5, -5)),
+            Coord::new((5, 5)),
+        ));
+    }
+
+    fn show<G: Grid<char>>(grid: &G) -> String {
+        grid.render(|c| c.map_or(".".to_string(), char::to_string))
+    }
+
+    fn check_transforms<G: Grid<char> + Default>(grid: G) {
+        assert_eq!(show(&grid.rotate_cw()), "eca\nfdb\n");
+        assert_eq!(show(&grid.rotate_ccw()), "bdf\nace\n");
+        assert_eq!(show(&grid.rotate_180()), "fe\ndc\nba\n");
+        assert_eq!(show(&grid.flip_horizontal()), "ba\ndc\nfe\n");
+        assert_eq!(show(&grid.flip_vertical()), "ef\ncd\nab\n");
+        assert_eq!(show(&grid.transpose()), "ace\nbdf\n");
+        let round_trip = grid.rotate_cw().rotate_cw().rotate_cw().rotate_cw();
+        assert_eq!(show(&round_trip), show(&grid));
+
+        let sub = grid.sub_grid(&(Coord::new((1, 1)), Coord::new((1, 2))));
+        assert_eq!(show(&sub), "d\nf\n");
+        assert_eq!(sub.bounds().0, Coord::new((0, 0)));
+
+        // The top left corner stays put, wherever the grid is
+        let mut moved = G::default();
+        moved.paste(&grid, Coord::new((5, -3)));
+        assert_eq!(
+            moved.rotate_cw().bounds(),
+            (Coord::new((5, -3)), Coord::new((7, -2)))
+        );
+        assert_eq!(show(&moved.rotate_cw()), "eca\nfdb\n");
+
+        assert_eq!(
+            grid.row(1).collect::<Vec<_>>(),
+            vec![Some(&'c'), Some(&'d')]
+        );
+        assert_eq!(
+            grid.column(0).collect::<Vec<_>>(),
+            vec![Some(&'a'), Some(&'c'), Some(&'e')]
+        );
+    }
+
+    #[test]
+    fn test_transforms() {
+        let map = "ab\ncd\nef";
+        check_transforms(HashGrid::parse(map, Some));
+        check_transforms(VecGrid::parse(map, Some));
+
+        let mut grid = VecGrid::parse(map, Some);
+        grid.set(Coord::new((-2, 0)), 'x');
+        assert_eq!(
+            grid.row_slice(0),
+            Some(&[Some('x'), None, Some('a'), Some('b')][..])
+        );
+        assert_eq!(grid.row_slices().count(), 3);
+        assert_eq!(grid.row_slice(3), None);
+
+        let column = grid.column_slice(0).unwrap();
+        assert_eq!(column.len(), 3);
+        assert_eq!(column.get(2), Some(&Some('e')));
+        assert_eq!(column.get(3), None);
+        let cells = |column: ColumnSlice<char>| column.iter().flatten().collect::<String>();
+        assert_eq!(cells(column), "ace");
+        assert_eq!(cells(column.slice(1..)), "ce");
+        assert_eq!(column.slice(..1).iter().rev().count(), 1);
+        assert!(column.slice(3..).is_empty());
+        assert_eq!(cells(grid.column_slice(-2).unwrap()), "x");
+        assert!(grid.column_slice(2).is_none());
+    }
+
+    #[test]
+    fn test_from_str_map() {
+        let input = "S.#\n.#E\n";
+        let to_cell = |c| (c != '.').then_some(c);
+
+        let (grid, markers) = HashGrid::from_str_map(input, "SE", to_cell);
+        assert_eq!(markers[&'S'], Coord::new((0, 0)));
+        assert_eq!(markers[&'E'], Coord::new((2, 1)));
+        assert_eq!(grid.data.len(), 4);
+        assert_eq!(grid.get(&Coord::new((1, 0))), None);
+        assert_eq!(grid.get(&Coord::new((2, 1))), Some(&'E'));
+
+        let (grid, markers) = VecGrid::from_str_map(input, "E", to_cell);
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[&'E'], Coord::new((2, 1)));
+        assert_eq!(grid.len(), 4);
+        assert_eq!(grid.get(&Coord::new((2, 0))), Some(&'#'));
+        assert_eq!(grid.bounds(), (Coord::new((0, 0)), Coord::new((2, 1))));
+
+        let digits = VecGrid::parse("12\n34", |c| c.to_digit(10));
+        assert_eq!(digits.get(&Coord::new((1, 1))), Some(&4));
+        assert!(VecGrid::parse("", Some).is_empty());
+    }
+
+    #[test]
+    fn test_vec_grid_grows() {
+        let mut grid = VecGrid::new();
+        for i in 0..50 {
+            grid.set(Coord::new((i, -i)), i);
+            grid.set(Coord::new((-i, i)), -i);
+        }
+        assert_eq!(grid.len(), 99);
+        assert_eq!(grid.width, 99);
+        assert_eq!(grid.height, 99);
+        assert_eq!(grid.bottom_left, Coord::new((-49, -49)));
+        assert!((0..50).all(|i| grid.get(&Coord::new((i, -i))) == Some(&i)));
+
+        *grid.get_mut(&Coord::new((3, -3))).unwrap() = 7;
+        assert_eq!(grid.get(&Coord::new((3, -3))), Some(&7));
+    }
+}
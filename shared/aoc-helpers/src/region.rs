@@ -0,0 +1,305 @@
+//! Flood fills and connected regions, for flat grids and voxel sets alike.
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use super::{Coord, Direction, Point3};
+
+/// A position whose face-adjacent cells are known: [`Coord`] on a grid, [`Point3`] in
+/// a voxel set.
+pub trait Cell: Copy + Eq + Hash {
+    /// The cells sharing an edge (or a face, in 3D) with this one.
+    fn adjacent(&self) -> impl Iterator<Item = Self>;
+
+    /// The smallest box holding every cell, grown by `margin` on each side.
+    fn bounding_box<'a>(
+        cells: impl IntoIterator<Item = &'a Self>,
+        margin: i32,
+    ) -> Option<(Self, Self)>
+    where
+        Self: 'a;
+
+    /// Whether this cell is inside a box from [`Cell::bounding_box`], edges included.
+    fn in_box(&self, bounds: &(Self, Self)) -> bool;
+}
+
+impl Cell for Coord {
+    fn adjacent(&self) -> impl Iterator<Item = Self> {
+        self.neighbors4()
+    }
+
+    fn bounding_box<'a>(
+        cells: impl IntoIterator<Item = &'a Self>,
+        margin: i32,
+    ) -> Option<(Self, Self)> {
+        cells
+            .into_iter()
+            .fold(None, |bounds: Option<(Coord, Coord)>, c| match bounds {
+                None => Some((*c, *c)),
+                Some((lo, hi)) => Some((
+                    Coord::new((lo.x.min(c.x), lo.y.min(c.y))),
+                    Coord::new((hi.x.max(c.x), hi.y.max(c.y))),
+                )),
+            })
+            .map(|(lo, hi)| (lo - (margin, margin), hi + (margin, margin)))
+    }
+
+    fn in_box(&self, bounds: &(Self, Self)) -> bool {
+        self.is_within(bounds)
+    }
+}
+
+impl Cell for Point3 {
+    fn adjacent(&self) -> impl Iterator<Item = Self> {
+        self.neighbors6()
+    }
+
+    fn bounding_box<'a>(
+        cells: impl IntoIterator<Item = &'a Self>,
+        margin: i32,
+    ) -> Option<(Self, Self)> {
+        let margin = Point3::from((margin, margin, margin));
+        cells
+            .into_iter()
+            .fold(None, |bounds: Option<(Point3, Point3)>, c| match bounds {
+                None => Some((*c, *c)),
+                Some((lo, hi)) => Some((
+                    Point3::from((lo.x.min(c.x), lo.y.min(c.y), lo.z.min(c.z))),
+                    Point3::from((hi.x.max(c.x), hi.y.max(c.y), hi.z.max(c.z))),
+                )),
+            })
+            .map(|(lo, hi)| (lo - margin, hi + margin))
+    }
+
+    fn in_box(&self, (lo, hi): &(Self, Self)) -> bool {
+        (lo.x..=hi.x).contains(&self.x)
+            && (lo.y..=hi.y).contains(&self.y)
+            && (lo.z..=hi.z).contains(&self.z)
+    }
+}
+
+/// Every cell reachable from `seed` through cells that are `passable`, `seed`
+/// included. Empty if `seed` itself isn't passable.
+pub fn flood_fill<C: Cell>(seed: C, mut passable: impl FnMut(&C) -> bool) -> HashSet<C> {
+    let mut filled = HashSet::new();
+    if !passable(&seed) {
+        return filled;
+    }
+    filled.insert(seed);
+    let mut to_visit = vec![seed];
+    while let Some(cell) = to_visit.pop() {
+        for next in cell.adjacent() {
+            if !filled.contains(&next) && passable(&next) {
+                filled.insert(next);
+                to_visit.push(next);
+            }
+        }
+    }
+    filled
+}
+
+/// Keeps removing cells from `cells` for as long as any of them is `removable`, and
+/// returns the removed cells in the order they went. Only the `neighbors` of a
+/// removed cell are looked at again, so removing a cell must never make a cell
+/// outside of them removable.
+pub fn peel<C: Cell, I: IntoIterator<Item = C>>(
+    cells: &mut HashSet<C>,
+    neighbors: impl Fn(&C) -> I,
+    mut removable: impl FnMut(&HashSet<C>, &C) -> bool,
+) -> Vec<C> {
+    let mut peeled = vec![];
+    let mut to_check: Vec<C> = cells.iter().copied().collect();
+    while let Some(cell) = to_check.pop() {
+        if !cells.contains(&cell) || !removable(cells, &cell) {
+            continue;
+        }
+        cells.remove(&cell);
+        peeled.push(cell);
+        to_check.extend(neighbors(&cell).into_iter().filter(|n| cells.contains(n)));
+    }
+    peeled
+}
+
+/// Splits `cells` into groups that are connected to each other.
+pub fn components<C: Cell>(cells: &HashSet<C>) -> Vec<HashSet<C>> {
+    let mut seen: HashSet<C> = HashSet::new();
+    let mut components = vec![];
+    for &cell in cells {
+        if seen.contains(&cell) {
+            continue;
+        }
+        let component = flood_fill(cell, |c| cells.contains(c));
+        seen.extend(&component);
+        components.push(component);
+    }
+    components
+}
+
+/// Labels connected runs of cells that carry the same value, e.g. the plots of a
+/// garden: `label(grid.iter())`.
+pub fn label<C: Cell, K: Eq + Clone>(
+    cells: impl IntoIterator<Item = (C, K)>,
+) -> Vec<(K, HashSet<C>)> {
+    let values: HashMap<C, K> = cells.into_iter().collect();
+    let mut seen: HashSet<C> = HashSet::new();
+    let mut regions = vec![];
+    for (&cell, value) in &values {
+        if seen.contains(&cell) {
+            continue;
+        }
+        let region = flood_fill(cell, |c| values.get(c) == Some(value));
+        seen.extend(&region);
+        regions.push((value.clone(), region));
+    }
+    regions
+}
+
+/// The number of edges (or faces, in 3D) between `region` and the cells around it.
+pub fn perimeter<C: Cell>(region: &HashSet<C>) -> usize {
+    region
+        .iter()
+        .flat_map(|c| c.adjacent())
+        .filter(|n| !region.contains(n))
+        .count()
+}
+
+/// The cells that can reach beyond the bounding box of `solid` without passing through
+/// it, limited to one cell around that box.
+pub fn exterior<C: Cell>(solid: &HashSet<C>) -> HashSet<C> {
+    let Some(bounds) = C::bounding_box(solid, 1) else {
+        return HashSet::new();
+    };
+    // The grown box's corner can't be solid, and every outside cell connects to it
+    flood_fill(bounds.0, |c| c.in_box(&bounds) && !solid.contains(c))
+}
+
+/// The pockets that are completely enclosed by `solid`.
+pub fn interior<C: Cell>(solid: &HashSet<C>) -> HashSet<C> {
+    let outside = exterior(solid);
+    solid
+        .iter()
+        .flat_map(|c| c.adjacent())
+        .filter(|n| !solid.contains(n) && !outside.contains(n))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .flat_map(|pocket| flood_fill(pocket, |c| !solid.contains(c)))
+        .collect()
+}
+
+/// Like [`perimeter`], but only counting the edges that face the [`exterior`].
+pub fn exterior_perimeter<C: Cell>(region: &HashSet<C>) -> usize {
+    let outside = exterior(region);
+    region
+        .iter()
+        .flat_map(|c| c.adjacent())
+        .filter(|n| outside.contains(n))
+        .count()
+}
+
+/// The number of straight sides around a flat `region`, which is the same as its
+/// number of corners.
+pub fn sides(region: &HashSet<Coord>) -> usize {
+    let inside = |c: Coord| region.contains(&c);
+    region
+        .iter()
+        .map(|&c| {
+            Direction::ALL
+                .into_iter()
+                .filter(|&dir| {
+                    let (ahead, right) = (inside(c + dir), inside(c + dir.right()));
+                    let diagonal = inside(c + dir + dir.right());
+                    // Convex corners stick out, concave ones fill in an L
+                    (!ahead && !right) || (ahead && right && !diagonal)
+                })
+                .count()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VecGrid;
+
+    fn cells(map: &str) -> HashSet<Coord> {
+        VecGrid::parse(map, |c| (c == '#').then_some(()))
+            .iter()
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let walls = cells("#####\n#..##\n#.#.#\n#####");
+        let open = |c: &Coord| {
+            c.is_within(&(Coord::new((0, 0)), Coord::new((4, 3)))) && !walls.contains(c)
+        };
+        assert_eq!(flood_fill(Coord::new((1, 1)), open).len(), 3);
+        assert_eq!(flood_fill(Coord::new((3, 2)), open).len(), 1);
+        assert!(flood_fill(Coord::new((0, 0)), open).is_empty());
+    }
+
+    #[test]
+    fn test_peel() {
+        // Peeling cells with fewer than two neighbors eats a line from its ends, but
+        // a ring holds together
+        let mut shape = cells("#####.\n......\n.###..\n.#.#..\n.###..");
+        let lonely = |shape: &HashSet<Coord>, c: &Coord| {
+            c.adjacent().filter(|n| shape.contains(n)).count() < 2
+        };
+        assert_eq!(peel(&mut shape, Coord::neighbors4, lonely).len(), 5);
+        assert_eq!(shape, cells("......\n......\n.###..\n.#.#..\n.###.."));
+        assert!(peel(&mut shape, Coord::neighbors4, lonely).is_empty());
+    }
+
+    #[test]
+    fn test_components_and_label() {
+        let region = cells("##..#\n#...#\n....#");
+        let mut sizes: Vec<usize> = components(&region).iter().map(HashSet::len).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![3, 3]);
+
+        // The example garden from AoC 2024 day 12
+        let garden = VecGrid::parse("AAAA\nBBCD\nBBCC\nEEEC", Some);
+        let mut prices: Vec<(char, usize, usize, usize)> = label(garden.iter())
+            .into_iter()
+            .map(|(&plant, plot)| (plant, plot.len(), perimeter(&plot), sides(&plot)))
+            .collect();
+        prices.sort();
+        assert_eq!(
+            prices,
+            vec![
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sides() {
+        // An E shape has 12 sides, and holes add their own
+        assert_eq!(sides(&cells("####\n#...\n####\n#...\n####")), 12);
+        assert_eq!(sides(&cells("###\n#.#\n###")), 8);
+    }
+
+    #[test]
+    fn test_exterior_and_interior() {
+        let ring = cells("###\n#.#\n###");
+        assert_eq!(interior(&ring), HashSet::from([Coord::new((1, 1))]));
+        assert_eq!(exterior(&ring).len(), 25 - 9);
+        assert_eq!(perimeter(&ring), 16);
+        assert_eq!(exterior_perimeter(&ring), 12);
+        assert!(interior(&HashSet::<Coord>::new()).is_empty());
+
+        // A 3x3x3 cube with its centre hollowed out
+        let mut cube: HashSet<Point3> = (0..27)
+            .map(|i| Point3::from((i % 3, i / 3 % 3, i / 9)))
+            .collect();
+        cube.remove(&Point3::from((1, 1, 1)));
+        assert_eq!(interior(&cube), HashSet::from([Point3::from((1, 1, 1))]));
+        assert_eq!(perimeter(&cube), 54 + 6);
+        assert_eq!(exterior_perimeter(&cube), 54);
+    }
+}
@@ -0,0 +1,265 @@
+//! Drawing grids as text: a cropped viewport, layers drawn over each other and
+//! recordings of a simulation's frames.
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+use std::{fs, io, thread};
+
+use super::{Bounds, Coord, Grid};
+
+/// Moves the cursor to the top left of the terminal, so the next frame is drawn
+/// over the last one.
+pub const HOME: &str = "\x1b[1;1H";
+/// Clears the terminal.
+pub const CLEAR: &str = "\x1b[2J";
+
+/// Command line flag that asks a simulation to draw its frames as it runs.
+pub const RENDER_FLAG: &str = "--render";
+
+/// Whether `--render` was passed, e.g. `cargo solve 14 -- --render`.
+pub fn enabled() -> bool {
+    std::env::args().any(|arg| arg == RENDER_FLAG)
+}
+
+/// Which way `y` grows on screen.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum YAxis {
+    /// Row 0 at the top, the way maps in puzzle inputs are drawn.
+    #[default]
+    Down,
+    /// Row 0 at the bottom, for puzzles that think of `U` as `y + 1`.
+    Up,
+}
+
+type Layer<'a> = Box<dyn Fn(&Coord) -> Option<String> + 'a>;
+
+/// Draws layers of cells as text, later layers on top of earlier ones. The viewport
+/// defaults to the bounds of everything drawn from a grid or a set of points.
+pub struct Renderer<'a> {
+    viewport: Option<Bounds>,
+    extent: Option<Bounds>,
+    y_axis: YAxis,
+    empty: String,
+    layers: Vec<Layer<'a>>,
+}
+
+impl Default for Renderer<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new() -> Self {
+        Renderer {
+            viewport: None,
+            extent: None,
+            y_axis: YAxis::Down,
+            empty: ".".to_string(),
+            layers: vec![],
+        }
+    }
+
+    /// Only draws the cells inside `bounds`.
+    pub fn with_viewport(mut self, bounds: Bounds) -> Self {
+        self.viewport = Some(bounds);
+        self
+    }
+
+    pub fn with_y_axis(mut self, y_axis: YAxis) -> Self {
+        self.y_axis = y_axis;
+        self
+    }
+
+    /// What to draw where no layer has anything, `.` by default.
+    pub fn with_empty(mut self, empty: impl Into<String>) -> Self {
+        self.empty = empty.into();
+        self
+    }
+
+    /// Draws whatever `f` returns for each cell. This doesn't grow the viewport, since
+    /// there's no telling where `f` has anything to draw.
+    pub fn layer(mut self, f: impl Fn(&Coord) -> Option<String> + 'a) -> Self {
+        self.layers.push(Box::new(f));
+        self
+    }
+
+    /// Draws every cell that is set in `grid`.
+    pub fn grid<T: 'a>(self, grid: &'a impl Grid<T>, f: impl Fn(&T) -> String + 'a) -> Self {
+        if grid.iter().next().is_none() {
+            return self;
+        }
+        self.extend(grid.bounds())
+            .layer(move |pos| grid.get(pos).map(&f))
+    }
+
+    /// Draws `glyph` at each of `points`, e.g. the piece that is currently falling.
+    pub fn points(self, points: impl IntoIterator<Item = Coord>, glyph: &str) -> Self {
+        let points: HashSet<Coord> = points.into_iter().collect();
+        let glyph = glyph.to_string();
+        self.extend_to(points.iter())
+            .layer(move |pos| points.contains(pos).then(|| glyph.clone()))
+    }
+
+    /// Draws a label at each position. Where positions repeat, the last label wins.
+    pub fn labels(self, labels: impl IntoIterator<Item = (Coord, String)>) -> Self {
+        let labels: HashMap<Coord, String> = labels.into_iter().collect();
+        self.extend_to(labels.keys())
+            .layer(move |pos| labels.get(pos).cloned())
+    }
+
+    fn extend_to<'p>(self, points: impl Iterator<Item = &'p Coord>) -> Self {
+        points.fold(self, |renderer, &pos| renderer.extend((pos, pos)))
+    }
+
+    fn extend(mut self, (bottom_left, top_right): Bounds) -> Self {
+        self.extent = Some(match self.extent {
+            None => (bottom_left, top_right),
+            Some((lo, hi)) => (
+                Coord::new((lo.x.min(bottom_left.x), lo.y.min(bottom_left.y))),
+                Coord::new((hi.x.max(top_right.x), hi.y.max(top_right.y))),
+            ),
+        });
+        self
+    }
+
+    /// The cells that will be drawn: the viewport, or everything that was drawn.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.viewport.or(self.extent)
+    }
+
+    /// Draws the top-most layer's cell at `pos`, if any layer has one.
+    pub fn cell(&self, pos: &Coord) -> Option<String> {
+        self.layers.iter().rev().find_map(|layer| layer(pos))
+    }
+
+    /// One line per row, each ending in a newline. Empty if there's nothing to draw.
+    pub fn render(&self) -> String {
+        let Some((bottom_left, top_right)) = self.bounds() else {
+            return String::new();
+        };
+        let rows: Vec<i32> = match self.y_axis {
+            YAxis::Down => (bottom_left.y..=top_right.y).collect(),
+            YAxis::Up => (bottom_left.y..=top_right.y).rev().collect(),
+        };
+        let mut s = String::new();
+        for y in rows {
+            for x in bottom_left.x..=top_right.x {
+                match self.cell(&Coord { x, y }) {
+                    Some(cell) => s += &cell,
+                    None => s += &self.empty,
+                }
+            }
+            s += "\n";
+        }
+        s
+    }
+
+    pub fn print(&self) {
+        println!("{}", self.render());
+    }
+}
+
+/// Prints `frame` over whatever was drawn last, for watching a simulation run.
+pub fn redraw(frame: &str) {
+    print!("{HOME}{frame}");
+    let _ = io::stdout().flush();
+}
+
+/// The frames of a simulation, kept to be played back or saved once it's done.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Recording {
+    pub frames: Vec<String>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, frame: impl Into<String>) {
+        self.frames.push(frame.into());
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Draws each frame over the last, waiting `delay` between them.
+    pub fn play(&self, delay: Duration) {
+        print!("{CLEAR}");
+        for frame in &self.frames {
+            redraw(frame);
+            thread::sleep(delay);
+        }
+    }
+
+    /// Writes every frame to `path`, each followed by a blank line.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl std::fmt::Display for Recording {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for frame in &self.frames {
+            writeln!(f, "{frame}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VecGrid;
+
+    #[test]
+    fn test_layers() {
+        let grid = VecGrid::parse("#..\n.#.", |c| (c == '#').then_some(c));
+        let renderer = Renderer::new()
+            .grid(&grid, |c| c.to_string())
+            .points([Coord::new((1, 1)), Coord::new((3, 0))], "@")
+            .labels([(Coord::new((0, 0)), "H".to_string())]);
+        assert_eq!(
+            renderer.bounds(),
+            Some((Coord::new((0, 0)), Coord::new((3, 1))))
+        );
+        assert_eq!(renderer.render(), "H..@\n.@..\n");
+        assert_eq!(Renderer::new().render(), "");
+    }
+
+    #[test]
+    fn test_viewport_and_y_axis() {
+        let origin = Coord::new((0, 0));
+        let renderer = Renderer::new()
+            .points([origin, Coord::new((5, 5))], "#")
+            .layer(|pos| (pos.x == pos.y).then(|| "\\".to_string()))
+            .with_viewport((Coord::new((-1, -1)), Coord::new((1, 1))))
+            .with_empty(" ");
+        assert_eq!(renderer.render(), "\\  \n \\ \n  \\\n");
+        assert_eq!(
+            renderer.with_y_axis(YAxis::Up).render(),
+            "  \\\n \\ \n\\  \n"
+        );
+    }
+
+    #[test]
+    fn test_recording() {
+        let mut recording = Recording::new();
+        assert!(recording.is_empty());
+        for x in 0..3 {
+            let frame = Renderer::new()
+                .points([Coord::new((x, 0))], "#")
+                .with_viewport((Coord::new((0, 0)), Coord::new((2, 0))));
+            recording.record(frame.render());
+        }
+        assert_eq!(recording.len(), 3);
+        assert_eq!(recording.to_string(), "#..\n\n.#.\n\n..#\n\n");
+    }
+}
@@ -0,0 +1,459 @@
+//! Searching state spaces: shortest paths with BFS, Dijkstra and A*, depth-limited
+//! DFS, and branch and bound for puzzles that ask for the best possible score.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A puzzle described as states and the moves between them. Only `start` and
+/// `successors` are required; which of the rest matter depends on the search.
+pub trait SearchProblem {
+    type State: Clone + Eq + Hash;
+
+    fn start(&self) -> Self::State;
+
+    /// The states one move away, with what each move costs.
+    fn successors(&self, state: &Self::State) -> impl IntoIterator<Item = (Self::State, usize)>;
+
+    fn is_goal(&self, _state: &Self::State) -> bool {
+        false
+    }
+
+    /// A lower bound on the cost from `state` to a goal, for [`astar`]. It must never
+    /// overestimate, or the path found might not be the shortest.
+    fn heuristic(&self, _state: &Self::State) -> usize {
+        0
+    }
+
+    /// How good a state is, for [`branch_and_bound`] to maximise.
+    fn score(&self, _state: &Self::State) -> usize {
+        0
+    }
+
+    /// The best score that could be reached from `state`, for [`branch_and_bound`]. It
+    /// must never underestimate, or the best state might be pruned.
+    fn upper_bound(&self, _state: &Self::State) -> usize {
+        usize::MAX
+    }
+}
+
+/// How much work a search did.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// States whose successors were looked at.
+    pub expanded: usize,
+    /// Successors that were queued to be looked at.
+    pub queued: usize,
+    /// Successors skipped because they couldn't lead anywhere better.
+    pub pruned: usize,
+    /// The most states waiting to be expanded at once.
+    pub max_frontier: usize,
+}
+
+impl Stats {
+    fn frontier(&mut self, len: usize) {
+        self.max_frontier = self.max_frontier.max(len);
+    }
+}
+
+/// The states from the start to a goal, and what it costs to follow them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S> {
+    pub states: Vec<S>,
+    pub cost: usize,
+}
+
+/// The best state [`branch_and_bound`] found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Best<S> {
+    pub state: S,
+    pub score: usize,
+}
+
+/// What a search found, if anything, and how hard it had to look.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome<T> {
+    pub found: Option<T>,
+    pub stats: Stats,
+}
+
+/// Every state seen so far, with how it was reached, so paths can be rebuilt.
+struct Visited<S> {
+    states: Vec<(S, Option<usize>)>,
+    best: HashMap<S, (usize, usize)>,
+}
+
+impl<S: Clone + Eq + Hash> Visited<S> {
+    fn new(start: S) -> Self {
+        let mut best = HashMap::new();
+        best.insert(start.clone(), (0, 0));
+        Visited {
+            states: vec![(start, None)],
+            best,
+        }
+    }
+
+    /// Records `state` if it's new or reached more cheaply than before, returning its
+    /// index.
+    fn reach(&mut self, state: S, cost: usize, parent: usize) -> Option<usize> {
+        if let Some(&(best, _)) = self.best.get(&state) {
+            if best <= cost {
+                return None;
+            }
+        }
+        let index = self.states.len();
+        self.best.insert(state.clone(), (cost, index));
+        self.states.push((state, Some(parent)));
+        Some(index)
+    }
+
+    /// Whether `index` is still the cheapest way found to its state.
+    fn is_current(&self, index: usize) -> bool {
+        self.best[&self.states[index].0].1 == index
+    }
+
+    fn path(&self, mut index: usize, cost: usize) -> Path<S> {
+        let mut states = vec![];
+        loop {
+            let (state, parent) = &self.states[index];
+            states.push(state.clone());
+            match parent {
+                Some(parent) => index = *parent,
+                None => break,
+            }
+        }
+        states.reverse();
+        Path { states, cost }
+    }
+}
+
+/// The path to a goal with the fewest moves, ignoring move costs.
+pub fn bfs<P: SearchProblem>(problem: &P) -> Outcome<Path<P::State>> {
+    let mut stats = Stats::default();
+    let mut visited = Visited::new(problem.start());
+    let mut queue = VecDeque::from([(0, 0)]);
+    while let Some((index, steps)) = queue.pop_front() {
+        let state = visited.states[index].0.clone();
+        if problem.is_goal(&state) {
+            let found = Some(visited.path(index, steps));
+            return Outcome { found, stats };
+        }
+        stats.expanded += 1;
+        for (next, _) in problem.successors(&state) {
+            match visited.reach(next, steps + 1, index) {
+                Some(next) => {
+                    queue.push_back((next, steps + 1));
+                    stats.queued += 1;
+                }
+                None => stats.pruned += 1,
+            }
+        }
+        stats.frontier(queue.len());
+    }
+    Outcome { found: None, stats }
+}
+
+/// The cheapest path to a goal.
+pub fn dijkstra<P: SearchProblem>(problem: &P) -> Outcome<Path<P::State>> {
+    best_first(problem, |_| 0)
+}
+
+/// The cheapest path to a goal, looking first where [`SearchProblem::heuristic`]
+/// says a goal is closest.
+pub fn astar<P: SearchProblem>(problem: &P) -> Outcome<Path<P::State>> {
+    best_first(problem, |state| problem.heuristic(state))
+}
+
+fn best_first<P: SearchProblem>(
+    problem: &P,
+    heuristic: impl Fn(&P::State) -> usize,
+) -> Outcome<Path<P::State>> {
+    let mut stats = Stats::default();
+    let start = problem.start();
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+    let mut visited = Visited::new(start);
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        // A cheaper way here was queued after this one
+        if !visited.is_current(index) {
+            continue;
+        }
+        let state = visited.states[index].0.clone();
+        if problem.is_goal(&state) {
+            let found = Some(visited.path(index, cost));
+            return Outcome { found, stats };
+        }
+        stats.expanded += 1;
+        for (next, step) in problem.successors(&state) {
+            let estimate = heuristic(&next);
+            match visited.reach(next, cost + step, index) {
+                Some(next) => {
+                    queue.push(Reverse((cost + step + estimate, cost + step, next)));
+                    stats.queued += 1;
+                }
+                None => stats.pruned += 1,
+            }
+        }
+        stats.frontier(queue.len());
+    }
+    Outcome { found: None, stats }
+}
+
+/// The cost of the cheapest paths to a goal, and how many different paths have
+/// that cost.
+pub fn count_shortest_paths<P: SearchProblem>(problem: &P) -> Option<(usize, usize)> {
+    let start = problem.start();
+    let mut best: HashMap<P::State, (usize, usize)> = HashMap::from([(start.clone(), (0, 1))]);
+    let mut queue = BinaryHeap::from([Reverse((0, 0))]);
+    let mut states = vec![start];
+    let mut index: HashMap<P::State, usize> = HashMap::from([(states[0].clone(), 0)]);
+    let mut found: Option<(usize, usize)> = None;
+    while let Some(Reverse((cost, i))) = queue.pop() {
+        let state = states[i].clone();
+        let (best_cost, paths) = best[&state];
+        if cost > best_cost {
+            continue;
+        }
+        if found.is_some_and(|(goal_cost, _)| cost > goal_cost) {
+            break;
+        }
+        if problem.is_goal(&state) {
+            // Other goals may be just as close, so keep going until the cost goes up
+            let total = found.map_or(0, |(_, total)| total);
+            found = Some((cost, total + paths));
+            continue;
+        }
+        for (next, step) in problem.successors(&state) {
+            let next_cost = cost + step;
+            match best.get_mut(&next) {
+                Some((known, count)) if *known == next_cost => *count += paths,
+                Some((known, _)) if *known < next_cost => {}
+                _ => {
+                    best.insert(next.clone(), (next_cost, paths));
+                    let j = *index.entry(next.clone()).or_insert_with(|| {
+                        states.push(next);
+                        states.len() - 1
+                    });
+                    queue.push(Reverse((next_cost, j)));
+                }
+            }
+        }
+    }
+    found
+}
+
+/// A depth-first search for any goal at most `max_depth` moves away. The path
+/// found isn't necessarily the shortest.
+pub fn dfs<P: SearchProblem>(problem: &P, max_depth: usize) -> Outcome<Path<P::State>> {
+    let mut stats = Stats::default();
+    let mut visited = Visited::new(problem.start());
+    let mut stack = vec![(0, 0)];
+    while let Some((index, depth)) = stack.pop() {
+        if !visited.is_current(index) {
+            continue;
+        }
+        let state = visited.states[index].0.clone();
+        if problem.is_goal(&state) {
+            let found = Some(visited.path(index, depth));
+            return Outcome { found, stats };
+        }
+        if depth == max_depth {
+            continue;
+        }
+        stats.expanded += 1;
+        for (next, _) in problem.successors(&state) {
+            // Reaching a state at a shallower depth leaves more moves to go on with
+            match visited.reach(next, depth + 1, index) {
+                Some(next) => {
+                    stack.push((next, depth + 1));
+                    stats.queued += 1;
+                }
+                None => stats.pruned += 1,
+            }
+        }
+        stats.frontier(stack.len());
+    }
+    Outcome { found: None, stats }
+}
+
+/// The state with the best [`SearchProblem::score`], searching depth first and
+/// skipping anything whose [`SearchProblem::upper_bound`] can't beat the best so far.
+/// States aren't remembered, so the moves shouldn't lead back to earlier states.
+pub fn branch_and_bound<P: SearchProblem>(problem: &P) -> Outcome<Best<P::State>> {
+    let mut stats = Stats::default();
+    let mut best: Option<Best<P::State>> = None;
+    let mut stack = vec![problem.start()];
+    while let Some(state) = stack.pop() {
+        stats.expanded += 1;
+        let score = problem.score(&state);
+        if best.as_ref().is_none_or(|best| score > best.score) {
+            best = Some(Best {
+                state: state.clone(),
+                score,
+            });
+        }
+        for (next, _) in problem.successors(&state) {
+            if best
+                .as_ref()
+                .is_some_and(|best| problem.upper_bound(&next) <= best.score)
+            {
+                stats.pruned += 1;
+                continue;
+            }
+            stack.push(next);
+            stats.queued += 1;
+        }
+        stats.frontier(stack.len());
+    }
+    Outcome { found: best, stats }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coord, VecGrid};
+
+    /// Walking around the walls of a maze, where `~` costs 9 to wade through.
+    struct Maze {
+        grid: VecGrid<char>,
+        start: Coord,
+        end: Coord,
+    }
+
+    impl Maze {
+        fn new(map: &str) -> Self {
+            let (grid, markers) = VecGrid::from_str_map(map, "SE", |c| (c != '#').then_some(c));
+            Maze {
+                grid,
+                start: markers[&'S'],
+                end: markers[&'E'],
+            }
+        }
+    }
+
+    impl SearchProblem for Maze {
+        type State = Coord;
+
+        fn start(&self) -> Coord {
+            self.start
+        }
+
+        fn successors(&self, pos: &Coord) -> impl IntoIterator<Item = (Coord, usize)> {
+            pos.neighbors4()
+                .filter_map(|next| match self.grid.get(&next)? {
+                    '~' => Some((next, 9)),
+                    _ => Some((next, 1)),
+                })
+        }
+
+        fn is_goal(&self, pos: &Coord) -> bool {
+            *pos == self.end
+        }
+
+        fn heuristic(&self, pos: &Coord) -> usize {
+            pos.manhattan(&self.end) as usize
+        }
+    }
+
+    const MAZE: &str = "\
+#######
+#S..~E#
+#.#.#.#
+#.....#
+#######";
+
+    #[test]
+    fn test_shortest_paths() {
+        let maze = Maze::new(MAZE);
+
+        let path = bfs(&maze).found.unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.states.first(), Some(&maze.start));
+        assert_eq!(path.states.last(), Some(&maze.end));
+        assert_eq!(path.states.len(), 5);
+
+        // Going around the water is cheaper than wading through it
+        let cheapest = dijkstra(&maze).found.unwrap();
+        assert_eq!(cheapest.cost, 8);
+        assert_eq!(cheapest.states.len(), 9);
+        let guided = astar(&maze);
+        assert_eq!(guided.found.unwrap().cost, 8);
+        assert!(guided.stats.expanded <= dijkstra(&maze).stats.expanded);
+
+        let walled_in = Maze::new("#####\n#S#E#\n#####");
+        assert_eq!(bfs(&walled_in).found, None);
+        assert_eq!(astar(&walled_in).found, None);
+    }
+
+    #[test]
+    fn test_count_shortest_paths() {
+        // Three ways around the pillars, all of length 6
+        let maze = Maze::new("#######\n#S....#\n#.#.#.#\n#....E#\n#######");
+        assert_eq!(count_shortest_paths(&maze), Some((6, 3)));
+        assert_eq!(
+            count_shortest_paths(&Maze::new("####\n#SE#\n####")),
+            Some((1, 1))
+        );
+        assert_eq!(
+            count_shortest_paths(&Maze::new("#####\n#S#E#\n#####")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dfs() {
+        let maze = Maze::new(MAZE);
+        let path = dfs(&maze, 10).found.unwrap();
+        assert_eq!(path.states.last(), Some(&maze.end));
+        assert_eq!(path.cost, path.states.len() - 1);
+        assert_eq!(dfs(&maze, 3).found, None);
+    }
+
+    /// Choosing items with a weight limit, to find the most valuable knapsack.
+    struct Knapsack {
+        items: Vec<(usize, usize)>,
+        capacity: usize,
+    }
+
+    impl SearchProblem for Knapsack {
+        // The next item to decide on, the weight so far and the value so far
+        type State = (usize, usize, usize);
+
+        fn start(&self) -> Self::State {
+            (0, 0, 0)
+        }
+
+        fn successors(
+            &self,
+            &(i, weight, value): &Self::State,
+        ) -> impl IntoIterator<Item = (Self::State, usize)> {
+            let Some(&(item_weight, item_value)) = self.items.get(i) else {
+                return vec![];
+            };
+            let mut next = vec![((i + 1, weight, value), 0)];
+            if weight + item_weight <= self.capacity {
+                next.push(((i + 1, weight + item_weight, value + item_value), 0));
+            }
+            next
+        }
+
+        fn score(&self, &(_, _, value): &Self::State) -> usize {
+            value
+        }
+
+        fn upper_bound(&self, &(i, _, value): &Self::State) -> usize {
+            value + self.items[i..].iter().map(|(_, v)| v).sum::<usize>()
+        }
+    }
+
+    #[test]
+    fn test_branch_and_bound() {
+        let knapsack = Knapsack {
+            items: vec![(5, 10), (4, 40), (6, 30), (3, 50)],
+            capacity: 10,
+        };
+        let outcome = branch_and_bound(&knapsack);
+        assert_eq!(outcome.found.unwrap().score, 90);
+        assert!(outcome.stats.pruned > 0);
+        // Without pruning all 2^4 ways of choosing are tried, less those too heavy
+        assert!(outcome.stats.expanded < 16 + 8 + 4 + 2 + 1);
+    }
+}
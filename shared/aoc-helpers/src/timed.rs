@@ -0,0 +1,180 @@
+//! Mazes whose obstacles move in a pattern that repeats, searched over position and
+//! time so that waiting in place is a move like any other.
+use super::bitset::BitSet;
+use super::search::{bfs, SearchProblem};
+use super::{Bounds, Coord};
+
+/// Which cells inside `bounds` are blocked at each step of a pattern that repeats
+/// every `period` steps. Everything outside `bounds` is always blocked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedGrid {
+    bounds: Bounds,
+    period: usize,
+    blocked: Vec<BitSet>,
+}
+
+impl TimedGrid {
+    /// Works out `blocked(t, pos)` for every cell and every `t` in `0..period` up
+    /// front, so the search only has to look up bits.
+    pub fn new(bounds: Bounds, period: usize, blocked: impl Fn(usize, &Coord) -> bool) -> Self {
+        let mut grid = TimedGrid {
+            bounds,
+            period,
+            blocked: vec![],
+        };
+        grid.blocked = (0..period)
+            .map(|t| {
+                grid.cells()
+                    .enumerate()
+                    .filter(|(_, pos)| blocked(t, pos))
+                    .map(|(i, _)| i)
+                    .collect()
+            })
+            .collect();
+        grid
+    }
+
+    /// Like [`TimedGrid::new`], but from where the obstacles are at each step, which is
+    /// quicker when they are few or easy to move along.
+    pub fn from_obstacles<I: IntoIterator<Item = Coord>>(
+        bounds: Bounds,
+        period: usize,
+        mut obstacles: impl FnMut(usize) -> I,
+    ) -> Self {
+        let mut grid = TimedGrid {
+            bounds,
+            period,
+            blocked: vec![],
+        };
+        grid.blocked = (0..period)
+            .map(|t| {
+                obstacles(t)
+                    .into_iter()
+                    .filter(|pos| pos.is_within(&bounds))
+                    .map(|pos| grid.index(&pos))
+                    .collect()
+            })
+            .collect();
+        grid
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Whether `pos` can be stood on at step `t`.
+    pub fn is_open(&self, t: usize, pos: &Coord) -> bool {
+        pos.is_within(&self.bounds) && !self.blocked[t % self.period].contains(self.index(pos))
+    }
+
+    /// The cells that can be stood on at step `t + 1` after standing on `pos` at step
+    /// `t`: staying put or one step in any direction.
+    pub fn moves(&self, t: usize, pos: &Coord) -> impl Iterator<Item = Coord> + '_ {
+        std::iter::once(*pos)
+            .chain(pos.neighbors4())
+            .filter(move |next| self.is_open(t + 1, next))
+    }
+
+    /// The fewest steps to visit each of `waypoints` in turn, setting off from the
+    /// first of them at step `start_time`.
+    pub fn trip(&self, start_time: usize, waypoints: &[Coord]) -> Option<usize> {
+        let trip = Trip {
+            grid: self,
+            start_time,
+            waypoints,
+        };
+        Some(bfs(&trip).found?.cost)
+    }
+
+    fn cells(&self) -> impl Iterator<Item = Coord> {
+        let (bottom_left, top_right) = self.bounds;
+        (bottom_left.y..=top_right.y)
+            .flat_map(move |y| (bottom_left.x..=top_right.x).map(move |x| Coord { x, y }))
+    }
+
+    fn index(&self, pos: &Coord) -> usize {
+        let (bottom_left, top_right) = self.bounds;
+        let width = top_right.x - bottom_left.x + 1;
+        ((pos.y - bottom_left.y) * width + pos.x - bottom_left.x) as usize
+    }
+}
+
+/// Where a [`Trip`] has got to: the position, the step modulo the period, and how
+/// many of the waypoints after the first have been reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TripState {
+    pub pos: Coord,
+    pub t: usize,
+    pub legs_done: usize,
+}
+
+/// Visiting waypoints in order on a [`TimedGrid`]. States that only differ by whole
+/// periods are the same, which keeps the search finite even when waiting.
+pub struct Trip<'a> {
+    pub grid: &'a TimedGrid,
+    pub start_time: usize,
+    pub waypoints: &'a [Coord],
+}
+
+impl SearchProblem for Trip<'_> {
+    type State = TripState;
+
+    fn start(&self) -> TripState {
+        TripState {
+            pos: self.waypoints[0],
+            t: self.start_time % self.grid.period,
+            legs_done: 0,
+        }
+    }
+
+    fn successors(&self, s: &TripState) -> impl IntoIterator<Item = (TripState, usize)> {
+        let t = (s.t + 1) % self.grid.period;
+        self.grid.moves(s.t, &s.pos).map(move |pos| {
+            let reached = self
+                .waypoints
+                .get(s.legs_done + 1)
+                .is_some_and(|&waypoint| waypoint == pos);
+            let legs_done = s.legs_done + reached as usize;
+            (TripState { pos, t, legs_done }, 1)
+        })
+    }
+
+    fn is_goal(&self, s: &TripState) -> bool {
+        s.legs_done + 1 >= self.waypoints.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trip() {
+        // A corridor with a gate in the middle that is only open every third step
+        let bounds = (Coord::new((0, 0)), Coord::new((4, 0)));
+        let grid = TimedGrid::new(bounds, 3, |t, pos| pos.x == 2 && t % 3 != 0);
+        assert!(grid.is_open(3, &Coord::new((2, 0))));
+        assert!(!grid.is_open(4, &Coord::new((2, 0))));
+        assert!(!grid.is_open(0, &Coord::new((5, 0))));
+
+        let (left, right) = (Coord::new((0, 0)), Coord::new((4, 0)));
+        // Walk up to the gate, wait a step for it to open at step 3, then carry on
+        assert_eq!(grid.trip(0, &[left, right]), Some(5));
+        // Setting off a step later gets there just in time, a step after that too late
+        assert_eq!(grid.trip(1, &[left, right]), Some(4));
+        assert_eq!(grid.trip(2, &[left, right]), Some(6));
+        assert_eq!(grid.trip(0, &[left, right, left]), Some(11));
+        assert_eq!(grid.trip(0, &[left]), Some(0));
+
+        // The same gate as an obstacle that is only there between openings
+        let gate = |t| (t % 3 != 0).then_some(Coord::new((2, 0)));
+        assert_eq!(TimedGrid::from_obstacles(bounds, 3, gate), grid);
+
+        let walled = TimedGrid::new(bounds, 1, |_, pos| pos.x == 2);
+        assert_eq!(walled.trip(0, &[left, right]), None);
+    }
+}